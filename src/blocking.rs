@@ -0,0 +1,640 @@
+//! Synchronous client for programs without an async runtime
+//!
+//! Mirrors [`crate::IndexnowClient`] and shares its request construction, retries and reports,
+//! but sends requests with a blocking [`Transport`] and submits to the endpoints from scoped
+//! threads.
+
+/// Sends built requests to an `IndexNow.org` endpoint and blocks until the response is received
+///
+/// Like [`crate::Transport`], implementations resolve to the response with its body already
+/// collected.
+pub trait Transport: Send + Sync {
+    fn send(
+        &self,
+        request: http::Request<bytes::Bytes>,
+    ) -> crate::Result<http::Response<bytes::Bytes>>;
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+    fn send(
+        &self,
+        request: http::Request<bytes::Bytes>,
+    ) -> crate::Result<http::Response<bytes::Bytes>> {
+        (**self).send(request)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn send(
+        &self,
+        request: http::Request<bytes::Bytes>,
+    ) -> crate::Result<http::Response<bytes::Bytes>> {
+        (**self).send(request)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    fn send(
+        &self,
+        request: http::Request<bytes::Bytes>,
+    ) -> crate::Result<http::Response<bytes::Bytes>> {
+        (**self).send(request)
+    }
+}
+
+/// Default blocking [`Transport`] using `ureq` with `rustls`
+#[derive(Clone)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl UreqTransport {
+    pub fn new() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().build(),
+        }
+    }
+
+    /// Transport giving up on requests that take longer than `timeout`, including the response
+    pub fn with_timeout(timeout: std::time::Duration) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(timeout).build(),
+        }
+    }
+}
+
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for UreqTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UreqTransport").finish_non_exhaustive()
+    }
+}
+
+impl Transport for UreqTransport {
+    fn send(
+        &self,
+        request: http::Request<bytes::Bytes>,
+    ) -> crate::Result<http::Response<bytes::Bytes>> {
+        let url = crate::transport::without_query(request.uri());
+        let transport_error =
+            |reason: Box<dyn std::error::Error + Send + Sync>| crate::IndexnowError::Transport {
+                url: url.clone(),
+                reason,
+            };
+
+        let mut ureq_request = self
+            .agent
+            .request(request.method().as_str(), &request.uri().to_string());
+        for (name, value) in request.headers() {
+            let value = value
+                .to_str()
+                .map_err(|reason| transport_error(Box::new(reason)))?;
+            ureq_request = ureq_request.set(name.as_str(), value);
+        }
+
+        let result = if request.body().is_empty() {
+            ureq_request.call()
+        } else {
+            ureq_request.send_bytes(request.body())
+        };
+
+        // Statuses other than success are answers as well, retries and outcomes depend on them
+        let response = match result {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(e)) => {
+                return Err(transport_error(Box::new(UreqTransportError(e))))
+            }
+        };
+
+        let mut builder = http::Response::builder().status(response.status());
+        for name in response.headers_names() {
+            for value in response.all(&name) {
+                builder = builder.header(name.as_str(), value);
+            }
+        }
+
+        let mut body = vec![];
+        std::io::Read::read_to_end(&mut response.into_reader(), &mut body)
+            .map_err(|reason| transport_error(Box::new(reason)))?;
+
+        builder
+            .body(bytes::Bytes::from(body))
+            .map_err(|reason| transport_error(Box::new(reason)))
+    }
+}
+
+/// `ureq` transport error without the request URL, as the URL may contain the key
+struct UreqTransportError(ureq::Transport);
+
+impl std::fmt::Display for UreqTransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.kind())?;
+        if let Some(message) = self.0.message() {
+            write!(f, ": {}", message)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for UreqTransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UreqTransportError")
+            .field("kind", &self.0.kind())
+            .field("message", &self.0.message())
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::error::Error for UreqTransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        std::error::Error::source(&self.0)
+    }
+}
+
+/// Blocking client submitting URLs to `IndexNow.org` endpoints, see [`IndexnowClient::builder`]
+///
+/// The client is cheap to clone, clones share the transport and settings.
+#[derive(Clone)]
+pub struct IndexnowClient {
+    inner: std::sync::Arc<ClientInner>,
+}
+
+struct ClientInner {
+    transport: std::sync::Arc<dyn Transport>,
+    settings: crate::client::ClientSettings,
+}
+
+/// Builder of a blocking [`IndexnowClient`]
+pub struct IndexnowClientBuilder {
+    transport: Option<std::sync::Arc<dyn Transport>>,
+    inner: crate::IndexnowClientBuilder,
+}
+
+impl IndexnowClient {
+    pub fn builder() -> IndexnowClientBuilder {
+        IndexnowClientBuilder {
+            transport: None,
+            inner: crate::IndexnowClient::builder(),
+        }
+    }
+
+    pub fn endpoints(&self) -> &[http::Uri] {
+        &self.inner.settings.endpoints
+    }
+
+    pub fn key(&self) -> &crate::Key {
+        &self.inner.settings.key
+    }
+
+    pub fn key_location(&self) -> Option<&http::Uri> {
+        self.inner.settings.key_location.as_ref()
+    }
+
+    /// Submits a single URL to all endpoints
+    pub fn submit_url(&self, url: crate::SubmissionUrl) -> crate::Result<crate::SubmissionReport> {
+        self.submit_urls(vec![url])
+    }
+
+    /// Submits URLs to all endpoints concurrently, grouped per host and split into batches
+    ///
+    /// See [`crate::IndexnowClient::submit_urls`].
+    pub fn submit_urls(
+        &self,
+        urls: Vec<crate::SubmissionUrl>,
+    ) -> crate::Result<crate::SubmissionReport> {
+        let settings = &self.inner.settings;
+        let mut stream = crate::StreamState::new(&settings.endpoints, &settings.options)?;
+
+        self.submit_chunk(urls, &mut stream)?;
+        stream.finish(&settings.options)
+    }
+
+    /// Submits one chunk of URLs like [`IndexnowClient::submit_urls`] and adds its report to the
+    /// stream, continuing the batch numbering of the stream
+    fn submit_chunk(
+        &self,
+        urls: Vec<crate::SubmissionUrl>,
+        stream: &mut crate::StreamState,
+    ) -> crate::Result<()> {
+        let settings = &self.inner.settings;
+        let options = &settings.options;
+
+        let (url_sets, skipped_urls) = crate::url_sets(
+            &settings.endpoints,
+            settings.key.clone(),
+            settings.key_location.clone(),
+            urls,
+            options,
+            stream,
+        )?;
+        let url_sets = &url_sets;
+        let batch_offset = stream.advance(url_sets.len());
+        let transport = &*self.inner.transport;
+
+        let batches: Vec<crate::BatchReport> = std::thread::scope(|scope| {
+            let threads: Vec<_> = settings
+                .endpoints
+                .iter()
+                .map(|endpoint| {
+                    scope.spawn(move || {
+                        let mut batches = vec![];
+
+                        for (index, url_set) in url_sets.iter().enumerate() {
+                            let start = std::time::Instant::now();
+                            let sent = send(transport, endpoint, url_set, options);
+
+                            batches.push(crate::batch_report(
+                                endpoint,
+                                batch_offset + index,
+                                url_set,
+                                sent,
+                                start.elapsed(),
+                            ));
+                        }
+
+                        batches
+                    })
+                })
+                .collect();
+
+            threads
+                .into_iter()
+                .flat_map(|thread| thread.join().expect("submission thread not to panic"))
+                .collect()
+        });
+
+        crate::finish_report(batches, skipped_urls, options, stream)
+    }
+
+    /// Submits URLs as they are produced by the iterator, one batch at a time
+    ///
+    /// See [`crate::IndexnowClient::submit_stream`].
+    pub fn submit_iter<I>(&self, urls: I) -> crate::Result<crate::SubmissionReport>
+    where
+        I: IntoIterator<Item = crate::SubmissionUrl>,
+    {
+        let settings = &self.inner.settings;
+        let mut stream = crate::StreamState::new(&settings.endpoints, &settings.options)?;
+        let mut urls = urls.into_iter().peekable();
+
+        while urls.peek().is_some() {
+            let chunk = urls.by_ref().take(settings.options.batch_size).collect();
+
+            if let Err(reason) = self.submit_chunk(chunk, &mut stream) {
+                return Err(stream.interrupt(reason));
+            }
+        }
+
+        stream.finish(&settings.options)
+    }
+
+    /// Builds the requests [`IndexnowClient::submit_urls`] would send, without sending them
+    pub fn build_requests(
+        &self,
+        urls: Vec<crate::SubmissionUrl>,
+    ) -> crate::Result<Vec<http::Request<bytes::Bytes>>> {
+        self.inner.settings.build_requests(urls)
+    }
+}
+
+impl std::fmt::Debug for IndexnowClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let settings = &self.inner.settings;
+
+        f.debug_struct("IndexnowClient")
+            .field("endpoints", &settings.endpoints)
+            .field("key", &settings.key)
+            .field("key_location", &settings.key_location)
+            .field("options", &settings.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IndexnowClientBuilder {
+    /// Transport to send requests with, defaults to [`UreqTransport`]
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(std::sync::Arc::new(transport));
+        self
+    }
+
+    /// Adds an endpoint to submit to, defaults to [`crate::DEFAULT_ENDPOINT`] if none are added
+    pub fn endpoint(mut self, endpoint: http::Uri) -> Self {
+        self.inner = self.inner.endpoint(endpoint);
+        self
+    }
+
+    pub fn endpoints(mut self, endpoints: impl IntoIterator<Item = http::Uri>) -> Self {
+        self.inner = self.inner.endpoints(endpoints);
+        self
+    }
+
+    /// Adds the endpoint of a known search engine
+    pub fn engine(mut self, engine: &crate::Engine) -> Self {
+        self.inner = self.inner.engine(engine);
+        self
+    }
+
+    pub fn key(mut self, key: crate::Key) -> Self {
+        self.inner = self.inner.key(key);
+        self
+    }
+
+    pub fn key_location(mut self, key_location: http::Uri) -> Self {
+        self.inner = self.inner.key_location(key_location);
+        self
+    }
+
+    /// Timeout of each request, applied by the default [`UreqTransport`]
+    ///
+    /// A custom transport is responsible for its own timeouts.
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.inner = self.inner.timeout(timeout);
+        self
+    }
+
+    pub fn retry(mut self, retry: crate::RetryPolicy) -> Self {
+        self.inner = self.inner.retry(retry);
+        self
+    }
+
+    pub fn host_policy(mut self, host_policy: crate::HostPolicy) -> Self {
+        self.inner = self.inner.host_policy(host_policy);
+        self
+    }
+
+    /// Maximum number of URLs per batch, capped at [`crate::MAX_URLS_PER_SET`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.inner = self.inner.batch_size(batch_size);
+        self
+    }
+
+    /// Ledger to record every submission in
+    pub fn ledger(mut self, ledger: crate::Ledger) -> Self {
+        self.inner = self.inner.ledger(ledger);
+        self
+    }
+
+    /// Skip URLs that every endpoint accepted within this window according to the ledger
+    pub fn skip_accepted_within(mut self, window: std::time::Duration) -> Self {
+        self.inner = self.inner.skip_accepted_within(window);
+        self
+    }
+
+    pub fn build(self) -> crate::Result<IndexnowClient> {
+        let settings = self.inner.settings()?;
+
+        let transport = self.transport.unwrap_or_else(|| {
+            std::sync::Arc::new(match settings.options.timeout {
+                Some(timeout) => UreqTransport::with_timeout(timeout),
+                None => UreqTransport::new(),
+            })
+        });
+
+        Ok(IndexnowClient {
+            inner: std::sync::Arc::new(ClientInner {
+                transport,
+                settings,
+            }),
+        })
+    }
+}
+
+impl std::fmt::Debug for IndexnowClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexnowClientBuilder")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends the URL set, retrying according to the policy, and returns the number of attempts made
+fn send<T: Transport + ?Sized>(
+    transport: &T,
+    endpoint: &http::Uri,
+    url_set: &crate::UrlSet,
+    options: &crate::SubmitOptions,
+) -> (crate::Result<crate::SubmissionOutcome>, u32) {
+    let mut attempts = 0;
+
+    let result = loop {
+        attempts += 1;
+
+        let response = match url_set
+            .request(endpoint.clone())
+            .and_then(|request| transport.send(request))
+        {
+            Ok(response) => response,
+            Err(e) => break Err(e),
+        };
+
+        match options.retry.retry_delay(attempts, &response) {
+            Some(delay) => std::thread::sleep(delay),
+            None => break Ok(crate::SubmissionOutcome::from_response(response)),
+        }
+    };
+
+    (result, attempts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_submit_urls() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport =
+            std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([200, 202]));
+
+        let client = IndexnowClient::builder()
+            .transport(transport.clone())
+            .engine(crate::Engine::find("bing").unwrap())
+            .engine(crate::Engine::find("yandex").unwrap())
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .build()?;
+
+        let report = client.clone().submit_urls(vec![
+            "https://www.example.com/url1".parse()?,
+            "https://www.example.com/url2".parse()?,
+        ])?;
+
+        assert!(report.is_accepted());
+        assert_eq!(report.batches.len(), 2);
+
+        let requests = transport.take_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method(), http::Method::POST);
+        assert_eq!(
+            client.build_requests(vec!["https://www.example.com/url1".parse()?])?[0].method(),
+            http::Method::GET
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ureq_transport_error_redacted() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        // Nothing listens on the port once the listener is dropped
+        let port = std::net::TcpListener::bind("127.0.0.1:0")?
+            .local_addr()?
+            .port();
+
+        let client = IndexnowClient::builder()
+            .transport(UreqTransport::new())
+            .endpoint(format!("http://127.0.0.1:{}/indexnow", port).parse()?)
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .build()?;
+
+        let report = client.submit_url("https://www.example.com/product.html".parse()?)?;
+
+        let error = report.batches[0]
+            .summary()
+            .error
+            .expect("transport error in summary");
+        assert!(error.contains("Connection Failed"), "{}", error);
+        assert!(!error.contains("687a308e4eff49f994d89eb22f764514"));
+        assert!(!format!("{:?}", report).contains("687a308e4eff49f994d89eb22f764514"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_submit_iter() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([
+            200, 200, 200,
+        ]));
+
+        let client = IndexnowClient::builder()
+            .transport(transport.clone())
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .batch_size(2)
+            .build()?;
+
+        let urls = (1..=5)
+            .map(|i| format!("https://www.example.com/url{}", i).parse())
+            .collect::<std::result::Result<Vec<crate::SubmissionUrl>, _>>()?;
+
+        let report = client.submit_iter(urls.clone())?;
+
+        assert_eq!(
+            report
+                .batches
+                .iter()
+                .map(crate::BatchReport::url_count)
+                .collect::<Vec<_>>(),
+            [2, 2, 1]
+        );
+        assert_eq!(transport.take_requests().len(), 3);
+
+        assert!(matches!(
+            client.submit_iter(Vec::new()),
+            Err(crate::IndexnowError::NoUrls)
+        ));
+
+        let client = IndexnowClient::builder()
+            .transport(crate::testing::FailingTransport)
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .batch_size(2)
+            .build()?;
+
+        let report = client.submit_iter(urls)?;
+
+        assert_eq!(
+            report
+                .batches
+                .iter()
+                .map(|batch| match &batch.result {
+                    Err(crate::IndexnowError::Batch { index, .. }) => *index,
+                    other => panic!("unexpected result: {:?}", other),
+                })
+                .collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_submit_iter_hosts() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let urls = [
+            "https://www.example.com/url1",
+            "https://www.example.com/url2",
+            "https://shop.example.com/url3",
+            "https://www.example.com/url4",
+        ]
+        .into_iter()
+        .map(str::parse)
+        .collect::<std::result::Result<Vec<crate::SubmissionUrl>, _>>()?;
+
+        let transport =
+            std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([200]));
+        let client = IndexnowClient::builder()
+            .transport(transport.clone())
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .batch_size(2)
+            .host_policy(crate::HostPolicy::Filter("shop.example.com".to_string()))
+            .build()?;
+
+        let report = client.submit_iter(urls.clone())?;
+        assert_eq!(report.batches.len(), 1);
+        assert_eq!(report.skipped_urls.len(), 3);
+
+        let transport =
+            std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([200]));
+        let client = IndexnowClient::builder()
+            .transport(transport.clone())
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .batch_size(2)
+            .host_policy(crate::HostPolicy::Reject)
+            .build()?;
+
+        match client.submit_iter(urls) {
+            Err(crate::IndexnowError::Interrupted { report, reason }) => {
+                assert_eq!(report.batches.len(), 1);
+                assert!(matches!(*reason, crate::IndexnowError::MixedHosts(_)));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(transport.take_requests().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_retry() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::new([
+            crate::testing::response(503, ""),
+            http::Response::builder()
+                .status(429)
+                .header(http::header::RETRY_AFTER, "0")
+                .body(bytes::Bytes::new())?,
+            crate::testing::response(200, ""),
+        ]);
+
+        let client = IndexnowClient::builder()
+            .transport(transport)
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .retry(crate::RetryPolicy {
+                max_attempts: 3,
+                base_delay: std::time::Duration::ZERO,
+                ..crate::RetryPolicy::default()
+            })
+            .build()?;
+
+        let report = client.submit_url("https://www.example.com/product.html".parse()?)?;
+
+        assert!(report.is_accepted());
+        assert_eq!(report.batches[0].attempts, 3);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,508 @@
+/// Client submitting URLs to `IndexNow.org` endpoints, see [`IndexnowClient::builder`]
+///
+/// The client is cheap to clone, clones share the transport and settings.
+#[derive(Clone)]
+pub struct IndexnowClient {
+    inner: std::sync::Arc<ClientInner>,
+}
+
+#[cfg_attr(not(any(feature = "tokio", test)), allow(dead_code))]
+struct ClientInner {
+    transport: std::sync::Arc<dyn crate::Transport>,
+    settings: ClientSettings,
+}
+
+/// Settings of a built client, shared with the blocking client
+#[derive(Debug)]
+pub(crate) struct ClientSettings {
+    pub(crate) endpoints: Vec<http::Uri>,
+    pub(crate) key: crate::Key,
+    pub(crate) key_location: Option<http::Uri>,
+    pub(crate) options: crate::SubmitOptions,
+}
+
+/// Builder of an [`IndexnowClient`]
+pub struct IndexnowClientBuilder {
+    transport: Option<std::sync::Arc<dyn crate::Transport>>,
+    endpoints: Vec<http::Uri>,
+    key: Option<crate::Key>,
+    key_location: Option<http::Uri>,
+    options: crate::SubmitOptions,
+}
+
+impl IndexnowClient {
+    pub fn builder() -> IndexnowClientBuilder {
+        IndexnowClientBuilder {
+            transport: None,
+            endpoints: vec![],
+            key: None,
+            key_location: None,
+            options: crate::SubmitOptions::default(),
+        }
+    }
+
+    pub fn endpoints(&self) -> &[http::Uri] {
+        &self.inner.settings.endpoints
+    }
+
+    pub fn key(&self) -> &crate::Key {
+        &self.inner.settings.key
+    }
+
+    pub fn key_location(&self) -> Option<&http::Uri> {
+        self.inner.settings.key_location.as_ref()
+    }
+
+    /// Submits a single URL to all endpoints
+    #[cfg(any(feature = "tokio", test))]
+    pub async fn submit_url(
+        &self,
+        url: crate::SubmissionUrl,
+    ) -> crate::Result<crate::SubmissionReport> {
+        self.submit_urls(vec![url]).await
+    }
+
+    /// Submits URLs to all endpoints concurrently, grouped per host and split into batches
+    ///
+    /// The returned report contains the result of each batch per endpoint, a failed batch does
+    /// not prevent the remaining batches from being submitted. With a ledger, the report is
+    /// recorded in it after submitting, and failing to do so fails the submission.
+    #[cfg(any(feature = "tokio", test))]
+    pub async fn submit_urls(
+        &self,
+        urls: Vec<crate::SubmissionUrl>,
+    ) -> crate::Result<crate::SubmissionReport> {
+        let settings = &self.inner.settings;
+
+        crate::submit(
+            &*self.inner.transport,
+            &settings.endpoints,
+            settings.key.clone(),
+            settings.key_location.clone(),
+            urls,
+            &settings.options,
+        )
+        .await
+    }
+
+    /// Submits URLs as they are produced by the stream, one batch at a time
+    ///
+    /// Each batch is submitted as soon as `batch_size` URLs are buffered or the stream has no URL
+    /// ready. Unlike [`IndexnowClient::submit_urls`], URLs are not grouped per host across
+    /// batches. Batches are numbered across the whole stream and the ledger is only read once,
+    /// URLs accepted earlier in the stream are skipped as well.
+    ///
+    /// With [`crate::HostPolicy::Filter`], batches without URLs of the host are skipped and the
+    /// submission only fails if no URL of the whole stream is of the host. Errors after batches
+    /// were submitted are returned as [`crate::IndexnowError::Interrupted`] with the report of
+    /// these batches.
+    #[cfg(any(feature = "tokio", test))]
+    pub async fn submit_stream<S>(&self, urls: S) -> crate::Result<crate::SubmissionReport>
+    where
+        S: futures_util::Stream<Item = crate::SubmissionUrl>,
+    {
+        use futures_util::StreamExt as _;
+
+        let settings = &self.inner.settings;
+        let mut stream = crate::StreamState::new(&settings.endpoints, &settings.options)?;
+
+        let chunks = urls.ready_chunks(settings.options.batch_size);
+        futures_util::pin_mut!(chunks);
+
+        while let Some(urls) = chunks.next().await {
+            let submitted = crate::submit_chunk(
+                &*self.inner.transport,
+                &settings.endpoints,
+                settings.key.clone(),
+                settings.key_location.clone(),
+                urls,
+                &settings.options,
+                &mut stream,
+            )
+            .await;
+
+            if let Err(reason) = submitted {
+                return Err(stream.interrupt(reason));
+            }
+        }
+
+        stream.finish(&settings.options)
+    }
+
+    /// Builds the requests [`IndexnowClient::submit_urls`] would send, without sending them
+    ///
+    /// The requests are in the order they would be sent for each endpoint, one per batch and
+    /// endpoint. URLs skipped by the host policy or the ledger are left out.
+    pub fn build_requests(
+        &self,
+        urls: Vec<crate::SubmissionUrl>,
+    ) -> crate::Result<Vec<http::Request<bytes::Bytes>>> {
+        self.inner.settings.build_requests(urls)
+    }
+}
+
+impl std::fmt::Debug for IndexnowClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let settings = &self.inner.settings;
+
+        f.debug_struct("IndexnowClient")
+            .field("endpoints", &settings.endpoints)
+            .field("key", &settings.key)
+            .field("key_location", &settings.key_location)
+            .field("options", &settings.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ClientSettings {
+    pub(crate) fn build_requests(
+        &self,
+        urls: Vec<crate::SubmissionUrl>,
+    ) -> crate::Result<Vec<http::Request<bytes::Bytes>>> {
+        crate::build_requests(
+            &self.endpoints,
+            self.key.clone(),
+            self.key_location.clone(),
+            urls,
+            &self.options,
+        )
+    }
+}
+
+impl IndexnowClientBuilder {
+    /// Transport to send requests with, defaults to [`crate::HyperTransport`] if enabled
+    pub fn transport(mut self, transport: impl crate::Transport + 'static) -> Self {
+        self.transport = Some(std::sync::Arc::new(transport));
+        self
+    }
+
+    /// Adds an endpoint to submit to, defaults to [`crate::DEFAULT_ENDPOINT`] if none are added
+    pub fn endpoint(mut self, endpoint: http::Uri) -> Self {
+        self.endpoints.push(endpoint);
+        self
+    }
+
+    pub fn endpoints(mut self, endpoints: impl IntoIterator<Item = http::Uri>) -> Self {
+        self.endpoints.extend(endpoints);
+        self
+    }
+
+    /// Adds the endpoint of a known search engine
+    pub fn engine(self, engine: &crate::Engine) -> Self {
+        self.endpoint(engine.endpoint())
+    }
+
+    pub fn key(mut self, key: crate::Key) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    pub fn key_location(mut self, key_location: http::Uri) -> Self {
+        self.key_location = Some(key_location);
+        self
+    }
+
+    /// Timeout of each request, including receiving the response
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    pub fn retry(mut self, retry: crate::RetryPolicy) -> Self {
+        self.options.retry = retry;
+        self
+    }
+
+    pub fn host_policy(mut self, host_policy: crate::HostPolicy) -> Self {
+        self.options.host_policy = host_policy;
+        self
+    }
+
+    /// Maximum number of URLs per batch, capped at [`crate::MAX_URLS_PER_SET`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be greater than zero");
+
+        self.options.batch_size = batch_size.min(crate::MAX_URLS_PER_SET);
+        self
+    }
+
+    /// Ledger to record every submission in
+    pub fn ledger(mut self, ledger: crate::Ledger) -> Self {
+        self.options.ledger = Some(ledger);
+        self
+    }
+
+    /// Skip URLs that every endpoint accepted within this window according to the ledger
+    pub fn skip_accepted_within(mut self, window: std::time::Duration) -> Self {
+        self.options.skip_accepted_within = Some(window);
+        self
+    }
+
+    pub fn build(mut self) -> crate::Result<IndexnowClient> {
+        let transport = self.transport.take();
+        let settings = self.settings()?;
+
+        #[cfg(feature = "hyper-rustls")]
+        let transport =
+            transport.unwrap_or_else(|| std::sync::Arc::new(crate::HyperTransport::new()));
+        #[cfg(not(feature = "hyper-rustls"))]
+        let transport = transport.ok_or(crate::IndexnowError::MissingTransport)?;
+
+        Ok(IndexnowClient {
+            inner: std::sync::Arc::new(ClientInner {
+                transport,
+                settings,
+            }),
+        })
+    }
+
+    /// Settings of the client to build, apart from the transport
+    pub(crate) fn settings(self) -> crate::Result<ClientSettings> {
+        let key = self.key.ok_or(crate::IndexnowError::MissingKey)?;
+
+        let mut endpoints = self.endpoints;
+        if endpoints.is_empty() {
+            endpoints.push(crate::DEFAULT_ENDPOINT.clone());
+        }
+
+        Ok(ClientSettings {
+            endpoints,
+            key,
+            key_location: self.key_location,
+            options: self.options,
+        })
+    }
+}
+
+impl std::fmt::Debug for IndexnowClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexnowClientBuilder")
+            .field("endpoints", &self.endpoints)
+            .field("key", &self.key)
+            .field("key_location", &self.key_location)
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_client_submit_urls() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport =
+            std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([200, 202]));
+
+        let client = IndexnowClient::builder()
+            .transport(transport.clone())
+            .engine(crate::Engine::find("bing").unwrap())
+            .engine(crate::Engine::find("yandex").unwrap())
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .build()?;
+
+        let report = client
+            .clone()
+            .submit_urls(vec![
+                "https://www.example.com/url1".parse()?,
+                "https://www.example.com/url2".parse()?,
+            ])
+            .await?;
+
+        assert!(report.is_accepted());
+        assert_eq!(report.batches.len(), 2);
+        assert_eq!(transport.take_requests().len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_client_submit_stream() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([
+            200, 200, 200,
+        ]));
+
+        let client = IndexnowClient::builder()
+            .transport(transport.clone())
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .batch_size(2)
+            .build()?;
+
+        let urls = (1..=5)
+            .map(|i| format!("https://www.example.com/url{}", i).parse())
+            .collect::<std::result::Result<Vec<crate::SubmissionUrl>, _>>()?;
+
+        let report = client
+            .submit_stream(futures_util::stream::iter(urls.clone()))
+            .await?;
+
+        assert_eq!(
+            report
+                .batches
+                .iter()
+                .map(crate::BatchReport::url_count)
+                .collect::<Vec<_>>(),
+            [2, 2, 1]
+        );
+        assert_eq!(client.endpoints(), [crate::DEFAULT_ENDPOINT.clone()]);
+
+        let requests = transport.take_requests();
+        assert_eq!(requests[2].method(), http::Method::GET);
+
+        assert!(matches!(
+            client
+                .submit_stream(futures_util::stream::iter(Vec::new()))
+                .await,
+            Err(crate::IndexnowError::NoUrls)
+        ));
+
+        let client = IndexnowClient::builder()
+            .transport(crate::testing::FailingTransport)
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .batch_size(2)
+            .build()?;
+
+        let report = client
+            .submit_stream(futures_util::stream::iter(urls))
+            .await?;
+
+        assert_eq!(
+            report
+                .batches
+                .iter()
+                .map(|batch| match &batch.result {
+                    Err(crate::IndexnowError::Batch { index, .. }) => *index,
+                    other => panic!("unexpected result: {:?}", other),
+                })
+                .collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_client_submit_stream_hosts() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let urls = [
+            "https://www.example.com/url1",
+            "https://www.example.com/url2",
+            "https://shop.example.com/url3",
+            "https://shop.example.com/url4",
+            "https://www.example.com/url5",
+        ]
+        .into_iter()
+        .map(str::parse)
+        .collect::<std::result::Result<Vec<crate::SubmissionUrl>, _>>()?;
+
+        let transport =
+            std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([200, 200]));
+        let client = IndexnowClient::builder()
+            .transport(transport.clone())
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .batch_size(2)
+            .host_policy(crate::HostPolicy::Filter("www.example.com".to_string()))
+            .build()?;
+
+        let report = client
+            .submit_stream(futures_util::stream::iter(urls.clone()))
+            .await?;
+        assert_eq!(report.batches.len(), 2);
+        assert_eq!(
+            report.skipped_urls,
+            [
+                "https://shop.example.com/url3",
+                "https://shop.example.com/url4"
+            ]
+        );
+        assert_eq!(transport.take_requests().len(), 2);
+
+        let result = client
+            .submit_stream(futures_util::stream::iter(urls[2..4].to_vec()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(crate::IndexnowError::NoMatchingHost { skipped: 2, .. })
+        ));
+
+        let transport =
+            std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([200]));
+        let client = IndexnowClient::builder()
+            .transport(transport.clone())
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .batch_size(2)
+            .host_policy(crate::HostPolicy::Reject)
+            .build()?;
+
+        let result = client
+            .submit_stream(futures_util::stream::iter(vec![
+                urls[0].clone(),
+                urls[1].clone(),
+                urls[2].clone(),
+                urls[4].clone(),
+            ]))
+            .await;
+        match result {
+            Err(crate::IndexnowError::Interrupted { report, reason }) => {
+                assert_eq!(report.batches.len(), 1);
+                assert_eq!(report.batches[0].url_count(), 2);
+                assert!(matches!(*reason, crate::IndexnowError::MixedHosts(_)));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(transport.take_requests().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_client_submit_stream_ledger(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join(format!(
+            "indexnow-stream-ledger-{}.ndjson",
+            std::process::id()
+        ));
+        let transport =
+            std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([200]));
+
+        let client = IndexnowClient::builder()
+            .transport(transport.clone())
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .batch_size(2)
+            .ledger(crate::Ledger::new(&path))
+            .skip_accepted_within(std::time::Duration::from_secs(3600))
+            .build()?;
+
+        let report = client
+            .submit_stream(futures_util::stream::iter(vec![
+                "https://www.example.com/url1".parse()?,
+                "https://www.example.com/url2".parse()?,
+                "https://www.example.com/url1".parse()?,
+            ]))
+            .await?;
+
+        assert_eq!(report.batches.len(), 1);
+        assert_eq!(report.skipped_urls, ["https://www.example.com/url1"]);
+        assert_eq!(transport.take_requests().len(), 1);
+
+        std::fs::remove_file(&path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_builder_missing_key() {
+        assert!(matches!(
+            IndexnowClient::builder()
+                .transport(crate::testing::ScriptedTransport::with_statuses(
+                    Vec::<u16>::new()
+                ))
+                .build(),
+            Err(crate::IndexnowError::MissingKey)
+        ));
+    }
+}
@@ -0,0 +1,205 @@
+/// Reason why a configuration file could not be loaded
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+
+    #[error("Unknown site {0:?}")]
+    UnknownSite(String),
+}
+
+/// Contents of an `indexnow.toml` configuration file
+///
+/// ```toml
+/// [site.blog]
+/// key = "687a308e4eff49f994d89eb22f764514"
+/// key_location = "https://blog.example.com/687a308e4eff49f994d89eb22f764514.txt"
+/// engines = ["bing", "yandex"]
+/// sitemap = "https://blog.example.com/sitemap.xml"
+/// ```
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Site profiles by name
+    #[serde(default)]
+    pub site: std::collections::BTreeMap<String, SiteProfile>,
+}
+
+/// Named profile of a site, providing defaults for submitting its URLs
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteProfile {
+    #[serde(default, deserialize_with = "parse_optional")]
+    pub key: Option<crate::Key>,
+
+    #[serde(default, deserialize_with = "parse_optional")]
+    pub key_location: Option<http::Uri>,
+
+    #[serde(default, deserialize_with = "parse_list")]
+    pub endpoints: Vec<http::Uri>,
+
+    /// Names of known search engines, see [`crate::ENGINES`]
+    #[serde(default)]
+    pub engines: Vec<String>,
+
+    #[serde(default, deserialize_with = "parse_optional")]
+    pub sitemap: Option<crate::Source>,
+
+    pub state: Option<std::path::PathBuf>,
+
+    #[serde(default, deserialize_with = "parse_optional")]
+    pub feed: Option<crate::Source>,
+
+    /// Only submit URLs of this host, or host and port, and skip all others
+    pub only_host: Option<String>,
+
+    #[serde(default)]
+    pub reject_mixed_hosts: bool,
+
+    pub retries: Option<u32>,
+
+    /// Maximum delay in seconds between retries
+    pub max_backoff: Option<u64>,
+
+    pub ledger: Option<std::path::PathBuf>,
+}
+
+impl Config {
+    /// Path of the configuration file in the XDG configuration directory,
+    /// `$XDG_CONFIG_HOME/indexnow/indexnow.toml` or `~/.config/indexnow/indexnow.toml`
+    pub fn default_path() -> Option<std::path::PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(std::path::PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".config"))
+            })?;
+
+        Some(config_home.join("indexnow").join("indexnow.toml"))
+    }
+
+    pub fn load(path: &std::path::Path) -> crate::Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| config_error(path, e.into()))?;
+
+        Self::parse(&content).map_err(|reason| config_error(path, reason))
+    }
+
+    /// Loads the configuration file at the default path, or an empty configuration if there is none
+    pub fn discover() -> crate::Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn parse(content: &str) -> std::result::Result<Self, ConfigError> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Site profile of the given name
+    pub fn site(&self, name: &str) -> std::result::Result<&SiteProfile, ConfigError> {
+        self.site
+            .get(name)
+            .ok_or_else(|| ConfigError::UnknownSite(name.to_string()))
+    }
+}
+
+fn config_error(path: &std::path::Path, reason: ConfigError) -> crate::IndexnowError {
+    crate::IndexnowError::Config {
+        location: path.display().to_string(),
+        reason,
+    }
+}
+
+fn parse_optional<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    use serde::{de::Error as _, Deserialize as _};
+
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(D::Error::custom))
+        .transpose()
+}
+
+fn parse_list<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    use serde::{de::Error as _, Deserialize as _};
+
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|s| s.parse().map_err(D::Error::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let config = Config::parse(
+            r#"
+[site.blog]
+key = "687a308e4eff49f994d89eb22f764514"
+key_location = "https://blog.example.com/687a308e4eff49f994d89eb22f764514.txt"
+engines = ["bing", "yandex"]
+sitemap = "https://blog.example.com/sitemap.xml"
+retries = 2
+
+[site.shop]
+endpoints = ["https://api.indexnow.org/indexnow"]
+only_host = "shop.example.com"
+"#,
+        )?;
+
+        let blog = config.site("blog")?;
+        assert_eq!(
+            blog.key.as_ref().map(crate::Key::expose_secret),
+            Some("687a308e4eff49f994d89eb22f764514")
+        );
+        assert_eq!(
+            blog.key_location,
+            Some("https://blog.example.com/687a308e4eff49f994d89eb22f764514.txt".parse()?)
+        );
+        assert_eq!(blog.engines, ["bing", "yandex"]);
+        assert_eq!(
+            blog.sitemap,
+            Some("https://blog.example.com/sitemap.xml".parse()?)
+        );
+        assert_eq!(blog.retries, Some(2));
+
+        let shop = config.site("shop")?;
+        assert_eq!(shop.endpoints, ["https://api.indexnow.org/indexnow"]);
+        assert_eq!(shop.only_host.as_deref(), Some("shop.example.com"));
+        assert!(shop.key.is_none());
+
+        assert!(matches!(
+            config.site("news"),
+            Err(ConfigError::UnknownSite(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(matches!(
+            Config::parse("[site.blog]\nkey = \"short\"\n"),
+            Err(ConfigError::Toml(_))
+        ));
+        assert!(matches!(
+            Config::parse("[site.blog]\nendpoint = \"https://www.bing.com/indexnow\"\n"),
+            Err(ConfigError::Toml(_))
+        ));
+    }
+}
@@ -0,0 +1,65 @@
+/// Search engine supporting the `IndexNow.org` protocol
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Engine {
+    pub name: &'static str,
+    pub endpoint: &'static str,
+}
+
+impl Engine {
+    /// Looks up a known engine by its case-insensitive name
+    pub fn find(name: &str) -> Option<&'static Engine> {
+        ENGINES
+            .iter()
+            .find(|engine| engine.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn endpoint(&self) -> http::Uri {
+        http::Uri::from_static(self.endpoint)
+    }
+}
+
+/// Known search engines, submissions to one of them are shared with all others
+pub static ENGINES: &[Engine] = &[
+    Engine {
+        name: "indexnow",
+        endpoint: "https://api.indexnow.org/indexnow",
+    },
+    Engine {
+        name: "bing",
+        endpoint: "https://www.bing.com/indexnow",
+    },
+    Engine {
+        name: "naver",
+        endpoint: "https://searchadvisor.naver.com/indexnow",
+    },
+    Engine {
+        name: "seznam",
+        endpoint: "https://search.seznam.cz/indexnow",
+    },
+    Engine {
+        name: "yandex",
+        endpoint: "https://yandex.com/indexnow",
+    },
+    Engine {
+        name: "yep",
+        endpoint: "https://indexnow.yep.com/indexnow",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engines() {
+        for engine in ENGINES {
+            assert_eq!(engine.endpoint().scheme_str(), Some("https"));
+        }
+
+        assert_eq!(
+            Engine::find("Bing").unwrap().endpoint(),
+            "https://www.bing.com/indexnow"
+        );
+        assert_eq!(Engine::find("google"), None);
+    }
+}
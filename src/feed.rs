@@ -0,0 +1,221 @@
+/// Linked item of an RSS feed or entry of an Atom feed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeedEntry {
+    pub link: http::Uri,
+
+    /// Latest of the published and updated dates
+    pub updated: Option<time::OffsetDateTime>,
+}
+
+/// Options for reading feeds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeedOptions {
+    /// Only read entries published or updated since, entries without dates are always read
+    pub since: Option<time::OffsetDateTime>,
+
+    /// Maximum size in bytes of the (decompressed) feed
+    pub max_size: usize,
+}
+
+impl Default for FeedOptions {
+    fn default() -> Self {
+        Self {
+            since: None,
+            max_size: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// Reason why a feed could not be parsed
+#[derive(Debug, thiserror::Error)]
+pub enum FeedError {
+    #[error("Invalid UTF-8")]
+    InvalidUtf8,
+
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+
+    #[error("Unexpected root element <{0}>")]
+    UnexpectedRoot(String),
+
+    #[error("Invalid URL {0:?}")]
+    InvalidUrl(String),
+}
+
+/// Reads the links of an RSS 2.0 or Atom feed
+pub async fn read_feed<T: crate::Transport + ?Sized>(
+    transport: &T,
+    source: &crate::Source,
+    options: &FeedOptions,
+) -> crate::Result<Vec<FeedEntry>> {
+    let content = crate::source::read(transport, source, options.max_size).await?;
+
+    let entries = parse(&content).map_err(|reason| crate::IndexnowError::InvalidFeed {
+        location: source.to_string(),
+        reason,
+    })?;
+
+    Ok(entries
+        .into_iter()
+        .filter(|entry| match (options.since, entry.updated) {
+            (Some(since), Some(updated)) => updated >= since,
+            _ => true,
+        })
+        .collect())
+}
+
+fn parse(content: &[u8]) -> std::result::Result<Vec<FeedEntry>, FeedError> {
+    let content = std::str::from_utf8(content).map_err(|_| FeedError::InvalidUtf8)?;
+    let document = roxmltree::Document::parse(content)?;
+    let root = document.root_element();
+
+    match root.tag_name().name() {
+        "rss" => crate::xml::children(root, "channel")
+            .flat_map(|channel| crate::xml::children(channel, "item"))
+            .filter_map(|item| {
+                let link = crate::xml::child_text(item, "link").or_else(|| {
+                    crate::xml::children(item, "guid")
+                        .find(|guid| guid.attribute("isPermaLink") != Some("false"))
+                        .and_then(|guid| guid.text())
+                        .map(str::trim)
+                })?;
+
+                Some(entry(
+                    link,
+                    [crate::xml::child_text(item, "pubDate")],
+                    &time::format_description::well_known::Rfc2822,
+                ))
+            })
+            .collect(),
+        "feed" => crate::xml::children(root, "entry")
+            .filter_map(|entry_node| {
+                let link = crate::xml::children(entry_node, "link")
+                    .find(|link| matches!(link.attribute("rel"), None | Some("alternate")))
+                    .and_then(|link| link.attribute("href"))?;
+
+                Some(entry(
+                    link,
+                    [
+                        crate::xml::child_text(entry_node, "published"),
+                        crate::xml::child_text(entry_node, "updated"),
+                    ],
+                    &time::format_description::well_known::Rfc3339,
+                ))
+            })
+            .collect(),
+        name => Err(FeedError::UnexpectedRoot(name.to_string())),
+    }
+}
+
+fn entry<const N: usize>(
+    link: &str,
+    dates: [Option<&str>; N],
+    format: &impl time::parsing::Parsable,
+) -> std::result::Result<FeedEntry, FeedError> {
+    Ok(FeedEntry {
+        link: crate::xml::absolute_url(link.trim())
+            .ok_or_else(|| FeedError::InvalidUrl(link.to_string()))?,
+        updated: dates
+            .into_iter()
+            .flatten()
+            .filter_map(|date| parse_date(date, format))
+            .max(),
+    })
+}
+
+fn parse_date(date: &str, format: &impl time::parsing::Parsable) -> Option<time::OffsetDateTime> {
+    time::OffsetDateTime::parse(date, format).ok().or_else(|| {
+        // RSS feeds commonly use the obsolete "GMT" and "UT" zones of RFC 822
+        let date = date
+            .strip_suffix(" GMT")
+            .or_else(|| date.strip_suffix(" UT"))?;
+        time::OffsetDateTime::parse(&format!("{} +0000", date), format).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_rss() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::new([crate::testing::response(
+            200,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>News</title>
+    <link>https://www.example.com/news/</link>
+    <item>
+      <title>New</title>
+      <link>https://www.example.com/news/new</link>
+      <pubDate>Tue, 02 Aug 2022 10:00:00 GMT</pubDate>
+    </item>
+    <item>
+      <title>Old</title>
+      <link>https://www.example.com/news/old</link>
+      <pubDate>Sun, 31 Jul 2022 10:00:00 +0000</pubDate>
+    </item>
+    <item>
+      <title>Permalink</title>
+      <guid>https://www.example.com/news/permalink</guid>
+    </item>
+  </channel>
+</rss>"#,
+        )]);
+
+        let entries = read_feed(
+            &transport,
+            &"https://www.example.com/news/feed.xml".parse()?,
+            &FeedOptions {
+                since: Some(time::macros::datetime!(2022-08-01 00:00 UTC)),
+                ..FeedOptions::default()
+            },
+        )
+        .await?;
+
+        assert_eq!(
+            entries,
+            [
+                FeedEntry {
+                    link: "https://www.example.com/news/new".parse()?,
+                    updated: Some(time::macros::datetime!(2022-08-02 10:00 UTC)),
+                },
+                FeedEntry {
+                    link: "https://www.example.com/news/permalink".parse()?,
+                    updated: None,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_atom() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let entries = parse(
+            br#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Blog</title>
+  <link href="https://blog.example.com/"/>
+  <entry>
+    <title>Post</title>
+    <link rel="edit" href="https://blog.example.com/edit/post"/>
+    <link href="https://blog.example.com/post"/>
+    <published>2022-08-01T10:00:00Z</published>
+    <updated>2022-08-03T10:00:00+02:00</updated>
+  </entry>
+</feed>"#,
+        )?;
+
+        assert_eq!(
+            entries,
+            [FeedEntry {
+                link: "https://blog.example.com/post".parse()?,
+                updated: Some(time::macros::datetime!(2022-08-03 08:00 UTC)),
+            }]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+/// Reason why the ledger could not be read or written
+#[derive(Debug, thiserror::Error)]
+pub enum LedgerError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Line {line}: {error}")]
+    Json {
+        line: usize,
+        error: serde_json::Error,
+    },
+}
+
+/// Submission of one URL to one endpoint
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LedgerRecord {
+    pub url: String,
+    pub endpoint: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: time::OffsetDateTime,
+
+    /// Name of the [`crate::SubmissionOutcome`], or `error` if there was no response
+    pub outcome: String,
+
+    pub status: Option<u16>,
+    pub accepted: bool,
+}
+
+/// Aggregated ledger records of one endpoint
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LedgerStats {
+    pub submissions: usize,
+    pub accepted: usize,
+    pub urls: usize,
+    pub last_submission: Option<time::OffsetDateTime>,
+}
+
+/// Append-only file of submissions, with one JSON record per line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ledger {
+    path: std::path::PathBuf,
+}
+
+impl Ledger {
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// Reads all records, or none if the ledger does not exist yet
+    pub fn records(&self) -> crate::Result<Vec<LedgerRecord>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(self.error(e.into())),
+        };
+
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|error| {
+                    self.error(LedgerError::Json {
+                        line: index + 1,
+                        error,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    pub fn append(&self, records: &[LedgerRecord]) -> crate::Result<()> {
+        use std::io::Write as _;
+
+        let mut content = vec![];
+        for record in records {
+            serde_json::to_writer(&mut content, record).expect("ledger record to be serializable");
+            content.push(b'\n');
+        }
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(&content))
+            .map_err(|e| self.error(e.into()))
+    }
+
+    /// Appends a record for every URL of every batch of the report
+    pub fn record(
+        &self,
+        report: &crate::SubmissionReport,
+        timestamp: time::OffsetDateTime,
+    ) -> crate::Result<()> {
+        let records: Vec<_> = report
+            .batches
+            .iter()
+            .flat_map(|batch| {
+                let summary = batch.summary();
+
+                batch.urls.iter().map(move |url| LedgerRecord {
+                    url: url.to_string(),
+                    endpoint: summary.endpoint.clone(),
+                    timestamp,
+                    outcome: summary.outcome.to_string(),
+                    status: summary.status,
+                    accepted: batch.is_accepted(),
+                })
+            })
+            .collect();
+
+        self.append(&records)
+    }
+
+    /// URLs accepted by every one of the endpoints since the given time
+    pub fn accepted_since(
+        &self,
+        endpoints: &[http::Uri],
+        since: time::OffsetDateTime,
+    ) -> crate::Result<std::collections::HashSet<String>> {
+        let mut accepted_endpoints =
+            std::collections::HashMap::<String, std::collections::HashSet<String>>::new();
+
+        for record in self.records()? {
+            if record.accepted && record.timestamp >= since {
+                accepted_endpoints
+                    .entry(record.url)
+                    .or_default()
+                    .insert(record.endpoint);
+            }
+        }
+
+        Ok(accepted_endpoints
+            .into_iter()
+            .filter(|(_, accepted_endpoints)| {
+                endpoints
+                    .iter()
+                    .all(|endpoint| accepted_endpoints.contains(&endpoint.to_string()))
+            })
+            .map(|(url, _)| url)
+            .collect())
+    }
+
+    fn error(&self, reason: LedgerError) -> crate::IndexnowError {
+        crate::IndexnowError::Ledger {
+            location: self.path.display().to_string(),
+            reason,
+        }
+    }
+}
+
+/// Aggregates records per endpoint
+pub fn ledger_stats(records: &[LedgerRecord]) -> std::collections::BTreeMap<String, LedgerStats> {
+    let mut stats = std::collections::BTreeMap::<String, LedgerStats>::new();
+    let mut urls = std::collections::HashSet::new();
+
+    for record in records {
+        let endpoint_stats = stats.entry(record.endpoint.clone()).or_default();
+
+        endpoint_stats.submissions += 1;
+        if record.accepted {
+            endpoint_stats.accepted += 1;
+        }
+        if urls.insert((&record.endpoint, &record.url)) {
+            endpoint_stats.urls += 1;
+        }
+        endpoint_stats.last_submission = endpoint_stats.last_submission.max(Some(record.timestamp));
+    }
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(url: &str, endpoint: &str, accepted: bool) -> LedgerRecord {
+        LedgerRecord {
+            url: url.to_string(),
+            endpoint: endpoint.to_string(),
+            timestamp: time::macros::datetime!(2022-08-01 00:00 UTC),
+            outcome: if accepted {
+                "accepted"
+            } else {
+                "too_many_requests"
+            }
+            .to_string(),
+            status: Some(if accepted { 200 } else { 429 }),
+            accepted,
+        }
+    }
+
+    #[test]
+    fn test_ledger() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let path =
+            std::env::temp_dir().join(format!("indexnow-ledger-{}.ndjson", std::process::id()));
+        let ledger = Ledger::new(&path);
+
+        assert!(ledger.records()?.is_empty());
+
+        let bing = "https://www.bing.com/indexnow";
+        let yandex = "https://yandex.com/indexnow";
+        let records = [
+            record("https://www.example.com/url1", bing, true),
+            record("https://www.example.com/url1", yandex, true),
+            record("https://www.example.com/url2", bing, true),
+            record("https://www.example.com/url2", yandex, false),
+            record("https://www.example.com/url3", bing, true),
+        ];
+        ledger.append(&records[..2])?;
+        ledger.append(&records[2..])?;
+
+        assert_eq!(ledger.records()?, records);
+
+        let accepted = ledger.accepted_since(
+            &[bing.parse()?, yandex.parse()?],
+            time::macros::datetime!(2022-08-01 00:00 UTC),
+        )?;
+        assert_eq!(
+            accepted,
+            ["https://www.example.com/url1".to_string()]
+                .into_iter()
+                .collect::<std::collections::HashSet<_>>()
+        );
+
+        let accepted = ledger.accepted_since(
+            &[bing.parse()?],
+            time::macros::datetime!(2022-08-02 00:00 UTC),
+        )?;
+        assert!(accepted.is_empty());
+
+        let stats = ledger_stats(&ledger.records()?);
+        assert_eq!(
+            stats[bing],
+            LedgerStats {
+                submissions: 3,
+                accepted: 3,
+                urls: 3,
+                last_submission: Some(time::macros::datetime!(2022-08-01 00:00 UTC)),
+            }
+        );
+        assert_eq!(stats[yandex].accepted, 1);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -1,16 +1,208 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod client;
+mod config;
+mod engine;
+mod feed;
+mod ledger;
+mod outcome;
+mod render;
+mod retry;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "tower")]
+pub mod service;
+mod sitemap;
+mod source;
+mod state;
+pub mod transport;
+mod url;
+mod url_list;
+mod verify;
+mod xml;
+
+#[cfg(test)]
+mod testing;
+
+pub use client::{IndexnowClient, IndexnowClientBuilder};
+pub use config::{Config, ConfigError, SiteProfile};
+pub use engine::{Engine, ENGINES};
+pub use feed::{read_feed, FeedEntry, FeedError, FeedOptions};
+pub use ledger::{ledger_stats, Ledger, LedgerError, LedgerRecord, LedgerStats};
+pub use outcome::{
+    BatchReport, BatchSummary, EndpointSummary, SubmissionOutcome, SubmissionReport,
+    SubmissionResponse,
+};
+pub use render::{render_requests, RequestFormat};
+pub use retry::RetryPolicy;
+pub use sitemap::{read_sitemap, SitemapEntry, SitemapError, SitemapLimits};
+pub use source::{ReadError, Source};
+pub use state::{SitemapChanges, SitemapState, StateError};
+pub use transport::Transport;
+pub use url::{SubmissionUrl, SubmissionUrlError};
+pub use url_list::{UrlList, UrlListError};
+pub use verify::{check_key_location_scope, key_file_url, verify_key_location, KeyFileError};
+
+#[cfg(feature = "hyper-rustls")]
+pub use transport::HyperTransport;
+
+/// Errors of this crate, sources carry the underlying cause where there is one
 #[derive(Debug, thiserror::Error)]
 pub enum IndexnowError {
     #[error("Invalid key")]
     InvalidKey,
 
-    #[error(transparent)]
-    Other(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("No URLs to submit")]
+    NoUrls,
+
+    #[error("No endpoints to submit to")]
+    NoEndpoints,
+
+    #[error("No key")]
+    MissingKey,
+
+    #[error("No transport")]
+    MissingTransport,
+
+    #[error("URL without host")]
+    MissingHost,
+
+    #[error("Invalid URL {url:?}")]
+    InvalidUrl {
+        url: String,
+        #[source]
+        reason: crate::SubmissionUrlError,
+    },
+
+    #[error("None of the {skipped} URLs are of host {host:?}")]
+    NoMatchingHost { host: String, skipped: usize },
+
+    #[error("URLs of multiple hosts: {}", .0.join(", "))]
+    MixedHosts(Vec<String>),
+
+    #[error("{} URLs outside the scope of key location {key_location}", .urls.len())]
+    OutOfScope {
+        key_location: http::Uri,
+        urls: Vec<http::Uri>,
+    },
+
+    #[error("Failed to URL-encode the query of the request to {endpoint}")]
+    UrlEncoding {
+        endpoint: http::Uri,
+        #[source]
+        reason: serde_urlencoded::ser::Error,
+    },
+
+    #[error("Invalid URI {uri:?}")]
+    InvalidUri {
+        uri: String,
+        #[source]
+        reason: http::Error,
+    },
+
+    #[error("Failed to serialize the URL set of {host}")]
+    Serialization {
+        host: String,
+        #[source]
+        reason: serde_json::Error,
+    },
+
+    #[error("Failed to send request to {url}")]
+    Transport {
+        url: http::Uri,
+        #[source]
+        reason: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+
+    #[error("Request to {url} timed out after {timeout:?}")]
+    Timeout {
+        url: http::Uri,
+        timeout: std::time::Duration,
+    },
+
+    #[error("Batch {} of {host} to {endpoint} failed", .index + 1)]
+    Batch {
+        endpoint: http::Uri,
+        host: String,
+        index: usize,
+        #[source]
+        reason: Box<IndexnowError>,
+    },
+
+    #[error("Submission stopped after {} batches", .report.batches.len())]
+    Interrupted {
+        /// Report of the batches submitted before
+        report: Box<crate::SubmissionReport>,
+        #[source]
+        reason: Box<IndexnowError>,
+    },
+
+    #[error("Failed to read {location}")]
+    Read {
+        location: String,
+        #[source]
+        reason: crate::ReadError,
+    },
+
+    #[error("Invalid sitemap {location}")]
+    InvalidSitemap {
+        location: String,
+        #[source]
+        reason: crate::SitemapError,
+    },
+
+    #[error("Invalid feed {location}")]
+    InvalidFeed {
+        location: String,
+        #[source]
+        reason: crate::FeedError,
+    },
+
+    #[error("Invalid configuration file {location}")]
+    Config {
+        location: String,
+        #[source]
+        reason: crate::ConfigError,
+    },
+
+    #[error("Invalid URL list {location}")]
+    InvalidUrlList {
+        location: String,
+        #[source]
+        reason: crate::UrlListError,
+    },
+
+    #[error("Failed to access ledger {location}")]
+    Ledger {
+        location: String,
+        #[source]
+        reason: crate::LedgerError,
+    },
+
+    #[error("Failed to access state file {location}")]
+    State {
+        location: String,
+        #[source]
+        reason: crate::StateError,
+    },
+
+    #[error("Invalid key file at {url}")]
+    InvalidKeyFile {
+        url: http::Uri,
+        #[source]
+        reason: crate::KeyFileError,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, crate::IndexnowError>;
 
-#[derive(Debug)]
-pub struct Key(String);
+/// Key to verify ownership of submitted URLs
+///
+/// The key is a secret until the key file is published, so it is redacted from `Debug` and
+/// `Display` output, compared in constant time and zeroized on drop. Use
+/// [`Key::expose_secret`] to access it.
+#[derive(Clone)]
+pub struct Key(zeroize::Zeroizing<String>);
 
 static KEY_REGEX: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
     regex::Regex::new("^[a-zA-Z0-9\\-]{8,128}$").expect("static regex to be parseable")
@@ -21,30 +213,503 @@ impl std::str::FromStr for Key {
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         if KEY_REGEX.is_match(s) {
-            Ok(Self(s.to_string()))
+            Ok(Self(s.to_string().into()))
         } else {
             Err(IndexnowError::InvalidKey)
         }
     }
 }
 
+impl Key {
+    pub const MIN_LENGTH: usize = 8;
+    pub const MAX_LENGTH: usize = 128;
+
+    /// Generates a cryptographically random key of `len` characters
+    pub fn generate(len: usize) -> Result<Self> {
+        use rand::Rng as _;
+
+        const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-";
+
+        if !(Self::MIN_LENGTH..=Self::MAX_LENGTH).contains(&len) {
+            return Err(IndexnowError::InvalidKey);
+        }
+
+        let mut rng = rand::rngs::OsRng;
+        let key: String = (0..len)
+            .map(|_| char::from(CHARSET[rng.gen_range(0..CHARSET.len())]))
+            .collect();
+
+        Ok(Self(key.into()))
+    }
+
+    /// The secret key, which should not be logged
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Name of the key file to be served at the root of the host, `{key}.txt`
+    pub fn file_name(&self) -> String {
+        format!("{}.txt", self.expose_secret())
+    }
+}
+
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Key")
+            .field(&format_args!("[redacted]"))
+            .finish()
+    }
+}
+
+impl std::fmt::Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        subtle::ConstantTimeEq::ct_eq(self.0.as_bytes(), other.0.as_bytes()).into()
+    }
+}
+
+impl Eq for Key {}
+
 pub static DEFAULT_ENDPOINT: once_cell::sync::Lazy<http::Uri> = once_cell::sync::Lazy::new(|| {
     "https://api.indexnow.org/indexnow"
         .try_into()
         .expect("static URL to be parseable")
 });
 
-pub async fn submit(
-    endpoint: http::Uri,
+/// Maximum number of URLs per submission as defined by the `IndexNow.org` protocol
+pub const MAX_URLS_PER_SET: usize = 10_000;
+
+/// How to submit URLs of multiple hosts, as each submission is limited to a single host
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum HostPolicy {
+    /// Submit separate batches per host
+    #[default]
+    Split,
+
+    /// Fail with [`IndexnowError::MixedHosts`]
+    Reject,
+
+    /// Only submit URLs of the given host, or host and port, and skip all others
+    ///
+    /// Fails with [`IndexnowError::NoMatchingHost`] if no URL is left to submit, for streams
+    /// only if no URL of the whole stream is of the host.
+    Filter(String),
+}
+
+impl HostPolicy {
+    /// Groups URLs by their authority, returning the groups and the skipped URLs
+    fn group(&self, urls: Vec<http::Uri>) -> Result<(Vec<Vec<http::Uri>>, Vec<http::Uri>)> {
+        let mut groups: Vec<(http::uri::Authority, Vec<http::Uri>)> = vec![];
+        let mut skipped = vec![];
+
+        for url in urls {
+            let authority = url
+                .authority()
+                .ok_or(crate::IndexnowError::MissingHost)?
+                .clone();
+
+            if let Self::Filter(host) = self {
+                if !authority.host().eq_ignore_ascii_case(host)
+                    && !authority.as_str().eq_ignore_ascii_case(host)
+                {
+                    skipped.push(url);
+                    continue;
+                }
+            }
+
+            match groups.iter_mut().find(|(group, _)| *group == authority) {
+                Some((_, group)) => group.push(url),
+                None => groups.push((authority, vec![url])),
+            }
+        }
+
+        if *self == Self::Reject && groups.len() > 1 {
+            return Err(crate::IndexnowError::MixedHosts(
+                groups
+                    .iter()
+                    .map(|(authority, _)| authority.to_string())
+                    .collect(),
+            ));
+        }
+
+        Ok((groups.into_iter().map(|(_, urls)| urls).collect(), skipped))
+    }
+}
+
+/// Options for [`submit`], set through the [`IndexnowClientBuilder`]
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    not(any(feature = "tokio", feature = "blocking", test)),
+    allow(dead_code)
+)]
+struct SubmitOptions {
+    host_policy: HostPolicy,
+    retry: crate::RetryPolicy,
+
+    /// Maximum number of URLs per batch
+    batch_size: usize,
+
+    /// Timeout of each request
+    timeout: Option<std::time::Duration>,
+
+    /// Ledger to record every submission in
+    ledger: Option<crate::Ledger>,
+
+    /// Skip URLs that every endpoint accepted within this window according to the ledger
+    skip_accepted_within: Option<std::time::Duration>,
+}
+
+impl Default for SubmitOptions {
+    fn default() -> Self {
+        Self {
+            host_policy: HostPolicy::default(),
+            retry: crate::RetryPolicy::default(),
+            batch_size: MAX_URLS_PER_SET,
+            timeout: None,
+            ledger: None,
+            skip_accepted_within: None,
+        }
+    }
+}
+
+/// State of a submission of URLs in chunks, e.g. from a stream
+#[derive(Debug)]
+#[cfg_attr(
+    not(any(feature = "tokio", feature = "blocking", test)),
+    allow(dead_code)
+)]
+struct StreamState {
+    /// Number of batches per endpoint of the previous chunks, so batches are numbered across
+    /// chunks
+    batch_offset: usize,
+
+    /// URLs every endpoint accepted within the window according to the ledger, read once
+    accepted: Option<std::collections::HashSet<String>>,
+
+    /// Whether any URL was of the host of [`HostPolicy::Filter`]
+    matched_host: bool,
+
+    /// Number of URLs skipped by [`HostPolicy::Filter`]
+    filtered_urls: usize,
+
+    /// Report of the chunks submitted so far
+    report: Option<crate::SubmissionReport>,
+}
+
+impl StreamState {
+    fn new(endpoints: &[http::Uri], options: &SubmitOptions) -> Result<Self> {
+        let accepted = match (&options.ledger, options.skip_accepted_within) {
+            (Some(ledger), Some(window)) => {
+                Some(ledger.accepted_since(endpoints, time::OffsetDateTime::now_utc() - window)?)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            batch_offset: 0,
+            accepted,
+            matched_host: false,
+            filtered_urls: 0,
+            report: None,
+        })
+    }
+
+    /// Fails with [`IndexnowError::NoMatchingHost`] if the host filter skipped all URLs so far
+    fn check_host_matched(&self, options: &SubmitOptions) -> Result<()> {
+        match &options.host_policy {
+            HostPolicy::Filter(host) if !self.matched_host && self.filtered_urls > 0 => {
+                Err(crate::IndexnowError::NoMatchingHost {
+                    host: host.clone(),
+                    skipped: self.filtered_urls,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds the report of a chunk to the report of the stream
+    #[cfg_attr(
+        not(any(feature = "tokio", feature = "blocking", test)),
+        allow(dead_code)
+    )]
+    fn add(&mut self, report: crate::SubmissionReport) {
+        match &mut self.report {
+            Some(stream_report) => {
+                stream_report.batches.extend(report.batches);
+                stream_report.skipped_urls.extend(report.skipped_urls);
+            }
+            None => self.report = Some(report),
+        }
+    }
+
+    /// Report of all chunks, failing if there were none or no URL was of the filtered host
+    #[cfg_attr(
+        not(any(feature = "tokio", feature = "blocking", test)),
+        allow(dead_code)
+    )]
+    fn finish(self, options: &SubmitOptions) -> Result<crate::SubmissionReport> {
+        self.check_host_matched(options)?;
+        self.report.ok_or(crate::IndexnowError::NoUrls)
+    }
+
+    /// Error stopping the stream, with the report of the batches submitted before, if any
+    #[cfg_attr(
+        not(any(feature = "tokio", feature = "blocking", test)),
+        allow(dead_code)
+    )]
+    fn interrupt(self, reason: crate::IndexnowError) -> crate::IndexnowError {
+        match self.report {
+            Some(report) if !report.batches.is_empty() => crate::IndexnowError::Interrupted {
+                report: Box::new(report),
+                reason: Box::new(reason),
+            },
+            _ => reason,
+        }
+    }
+
+    /// Index of the first of the next batches, counting them as submitted
+    #[cfg_attr(
+        not(any(feature = "tokio", feature = "blocking", test)),
+        allow(dead_code)
+    )]
+    fn advance(&mut self, batches: usize) -> usize {
+        let batch_offset = self.batch_offset;
+        self.batch_offset += batches;
+        batch_offset
+    }
+}
+
+/// Submits URLs to all endpoints concurrently, grouped per host and split into batches
+#[cfg(any(feature = "tokio", test))]
+async fn submit<T: crate::Transport + ?Sized>(
+    transport: &T,
+    endpoints: &[http::Uri],
     key: crate::Key,
     key_location: Option<http::Uri>,
-    urls: Vec<http::Uri>,
+    urls: Vec<crate::SubmissionUrl>,
+    options: &SubmitOptions,
+) -> Result<crate::SubmissionReport> {
+    let mut stream = StreamState::new(endpoints, options)?;
+
+    submit_chunk(
+        transport,
+        endpoints,
+        key,
+        key_location,
+        urls,
+        options,
+        &mut stream,
+    )
+    .await?;
+
+    stream.finish(options)
+}
+
+/// Submits one chunk of URLs like [`submit`] and adds its report to the stream, continuing the
+/// batch numbering of the stream
+#[cfg(any(feature = "tokio", test))]
+async fn submit_chunk<T: crate::Transport + ?Sized>(
+    transport: &T,
+    endpoints: &[http::Uri],
+    key: crate::Key,
+    key_location: Option<http::Uri>,
+    urls: Vec<crate::SubmissionUrl>,
+    options: &SubmitOptions,
+    stream: &mut StreamState,
 ) -> Result<()> {
-    if urls.len() == 1 {
-        let request = submit_one_request(endpoint, key, key_location, urls[0].clone()).unwrap();
-        println!("Request: {:?}", request);
+    let (url_sets, skipped_urls) = url_sets(endpoints, key, key_location, urls, options, stream)?;
+    let url_sets = &url_sets;
+    let batch_offset = stream.advance(url_sets.len());
+
+    let batches = futures_util::future::join_all(endpoints.iter().map(|endpoint| async move {
+        let mut batches = vec![];
+
+        for (index, url_set) in url_sets.iter().enumerate() {
+            let start = std::time::Instant::now();
+            let sent = send(transport, endpoint, url_set, options).await;
+
+            batches.push(batch_report(
+                endpoint,
+                batch_offset + index,
+                url_set,
+                sent,
+                start.elapsed(),
+            ));
+        }
+
+        batches
+    }))
+    .await;
+
+    finish_report(
+        batches.into_iter().flatten().collect(),
+        skipped_urls,
+        options,
+        stream,
+    )
+}
+
+/// Reports the result and number of attempts of sending a batch to the endpoint
+#[cfg(any(feature = "tokio", feature = "blocking", test))]
+fn batch_report(
+    endpoint: &http::Uri,
+    index: usize,
+    url_set: &UrlSet,
+    (result, attempts): (Result<crate::SubmissionOutcome>, u32),
+    latency: std::time::Duration,
+) -> crate::BatchReport {
+    crate::BatchReport {
+        endpoint: endpoint.clone(),
+        host: url_set.host().to_string(),
+        urls: url_set.urls().to_vec(),
+        attempts,
+        latency,
+        result: result.map_err(|reason| crate::IndexnowError::Batch {
+            endpoint: endpoint.clone(),
+            host: url_set.host().to_string(),
+            index,
+            reason: Box::new(reason),
+        }),
     }
-    todo!();
+}
+
+/// Collects the batches into a report, records it in the ledger, if any, and adds it to the
+/// stream
+///
+/// The accepted URLs are also skipped by the following chunks of the stream. The report is added
+/// even if recording it fails, as its batches were submitted.
+#[cfg(any(feature = "tokio", feature = "blocking", test))]
+fn finish_report(
+    batches: Vec<crate::BatchReport>,
+    skipped_urls: Vec<http::Uri>,
+    options: &SubmitOptions,
+    stream: &mut StreamState,
+) -> Result<()> {
+    let report = crate::SubmissionReport {
+        batches,
+        skipped_urls,
+    };
+
+    let recorded = match &options.ledger {
+        Some(ledger) => ledger.record(&report, time::OffsetDateTime::now_utc()),
+        None => Ok(()),
+    };
+    if let Some(accepted) = &mut stream.accepted {
+        accepted.extend(report.accepted_urls().iter().map(http::Uri::to_string));
+    }
+    stream.add(report);
+
+    recorded
+}
+
+/// Builds the requests [`submit`] would send, without sending them
+fn build_requests(
+    endpoints: &[http::Uri],
+    key: crate::Key,
+    key_location: Option<http::Uri>,
+    urls: Vec<crate::SubmissionUrl>,
+    options: &SubmitOptions,
+) -> Result<Vec<http::Request<bytes::Bytes>>> {
+    let mut stream = StreamState::new(endpoints, options)?;
+    let (url_sets, _) = url_sets(endpoints, key, key_location, urls, options, &mut stream)?;
+    stream.check_host_matched(options)?;
+
+    endpoints
+        .iter()
+        .flat_map(|endpoint| {
+            url_sets
+                .iter()
+                .map(move |url_set| url_set.request(endpoint.clone()))
+        })
+        .collect()
+}
+
+/// Groups and batches the URLs to submit, returning the batches and the skipped URLs
+///
+/// URLs skipped by [`HostPolicy::Filter`] are counted in the stream, see
+/// [`StreamState::check_host_matched`].
+fn url_sets(
+    endpoints: &[http::Uri],
+    key: crate::Key,
+    key_location: Option<http::Uri>,
+    urls: Vec<crate::SubmissionUrl>,
+    options: &SubmitOptions,
+    stream: &mut StreamState,
+) -> Result<(Vec<UrlSet>, Vec<http::Uri>)> {
+    if endpoints.is_empty() {
+        return Err(crate::IndexnowError::NoEndpoints);
+    }
+    if urls.is_empty() {
+        return Err(crate::IndexnowError::NoUrls);
+    }
+
+    let urls = urls.into_iter().map(http::Uri::from);
+    let (urls, mut skipped_urls): (Vec<http::Uri>, _) = match &stream.accepted {
+        Some(accepted) => urls.partition(|url| !accepted.contains(&url.to_string())),
+        None => (urls.collect(), vec![]),
+    };
+
+    let (groups, filtered_urls) = options.host_policy.group(urls)?;
+    stream.matched_host |= !groups.is_empty();
+    stream.filtered_urls += filtered_urls.len();
+    skipped_urls.extend(filtered_urls);
+
+    let mut url_sets = vec![];
+    for urls in groups {
+        url_sets.extend(
+            UrlSet::new(key.clone(), key_location.clone(), urls)?.batches(options.batch_size),
+        );
+    }
+
+    Ok((url_sets, skipped_urls))
+}
+
+/// Sends the URL set, retrying according to the policy, and returns the number of attempts made
+#[cfg(any(feature = "tokio", test))]
+async fn send<T: crate::Transport + ?Sized>(
+    transport: &T,
+    endpoint: &http::Uri,
+    url_set: &UrlSet,
+    options: &SubmitOptions,
+) -> (Result<crate::SubmissionOutcome>, u32) {
+    let mut attempts = 0;
+
+    let result = loop {
+        attempts += 1;
+
+        let request = match url_set.request(endpoint.clone()) {
+            Ok(request) => request,
+            Err(e) => break Err(e),
+        };
+
+        let response = transport.send(request.map(crate::transport::Body::new));
+        let response = match options.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, response).await {
+                Ok(response) => response,
+                Err(_) => Err(crate::IndexnowError::Timeout {
+                    url: endpoint.clone(),
+                    timeout,
+                }),
+            },
+            None => response.await,
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => break Err(e),
+        };
+
+        match options.retry.retry_delay(attempts, &response) {
+            Some(delay) => tokio::time::sleep(delay).await,
+            None => break Ok(crate::SubmissionOutcome::from_response(response)),
+        }
+    };
+
+    (result, attempts)
 }
 
 fn submit_one_request(
@@ -52,40 +717,185 @@ fn submit_one_request(
     key: crate::Key,
     key_location: Option<http::Uri>,
     url: http::Uri,
-) -> Result<http::Request<()>> {
-    let mut query = vec![("url", url.to_string()), ("key", key.0)];
+) -> Result<http::Request<bytes::Bytes>> {
+    let mut query = vec![
+        ("url", url.to_string()),
+        ("key", key.expose_secret().to_string()),
+    ];
 
     if let Some(key_location) = key_location {
         query.push(("keyLocation", key_location.to_string()));
     }
 
-    let mut path_and_query = endpoint.path().to_owned();
-    path_and_query.push('?');
-    path_and_query.push_str(
-        &serde_urlencoded::to_string(query)
-            .map_err(|e| crate::IndexnowError::Other(Box::new(e)))?,
-    );
+    let query =
+        serde_urlencoded::to_string(query).map_err(|reason| crate::IndexnowError::UrlEncoding {
+            endpoint: endpoint.clone(),
+            reason,
+        })?;
+    let path_and_query = format!("{}?{}", endpoint.path(), query);
 
+    // Errors name the endpoint only, as the query holds the key
     let mut parts = endpoint.clone().into_parts();
     parts.path_and_query = Some(
         path_and_query
             .parse()
-            .map_err(|e| crate::IndexnowError::Other(Box::new(e)))?,
+            .map_err(|reason: http::uri::InvalidUri| invalid_uri(&endpoint.to_string(), reason))?,
     );
 
-    let request = http::Request::builder()
-        .uri(http::Uri::from_parts(parts).map_err(|e| crate::IndexnowError::Other(Box::new(e)))?)
-        .method(http::Method::GET);
+    let uri = http::Uri::from_parts(parts)
+        .map_err(|reason| invalid_uri(&endpoint.to_string(), reason))?;
 
-    Ok(request
-        .body(())
-        .map_err(|e| crate::IndexnowError::Other(Box::new(e)))?)
+    http::Request::builder()
+        .uri(uri)
+        .method(http::Method::GET)
+        .body(bytes::Bytes::new())
+        .map_err(|reason| invalid_uri(&endpoint.to_string(), reason))
 }
 
-#[derive(Debug, serde::Serialize)]
-struct UrlSet {
+fn invalid_uri(uri: &str, reason: impl Into<http::Error>) -> crate::IndexnowError {
+    crate::IndexnowError::InvalidUri {
+        uri: uri.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// URLs of one host to be submitted together
+#[derive(Clone, Debug)]
+pub struct UrlSet {
     host: String,
-    key: String,
+    key: crate::Key,
+    key_location: Option<http::Uri>,
+    url_list: Vec<http::Uri>,
+}
+
+impl UrlSet {
+    /// Creates a set of URLs that all share the same host and are within the scope of the key
+    /// location
+    pub fn new(
+        key: crate::Key,
+        key_location: Option<http::Uri>,
+        urls: Vec<http::Uri>,
+    ) -> Result<Self> {
+        let authority = urls
+            .first()
+            .ok_or(crate::IndexnowError::NoUrls)?
+            .authority()
+            .ok_or(crate::IndexnowError::MissingHost)?;
+
+        if let Some(other) = urls
+            .iter()
+            .filter_map(http::Uri::authority)
+            .find(|other| *other != authority)
+        {
+            return Err(crate::IndexnowError::MixedHosts(vec![
+                authority.to_string(),
+                other.to_string(),
+            ]));
+        }
+        if urls.iter().any(|url| url.authority().is_none()) {
+            return Err(crate::IndexnowError::MissingHost);
+        }
+
+        if let Some(key_location) = &key_location {
+            crate::check_key_location_scope(key_location, &urls)?;
+        }
+
+        let host = authority.host().to_string();
+
+        Ok(Self {
+            host,
+            key,
+            key_location,
+            url_list: urls,
+        })
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn urls(&self) -> &[http::Uri] {
+        &self.url_list
+    }
+
+    pub fn len(&self) -> usize {
+        self.url_list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.url_list.is_empty()
+    }
+
+    /// Splits into sets of at most `batch_size` URLs, capped at [`MAX_URLS_PER_SET`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn batches(self, batch_size: usize) -> UrlSetBatches {
+        assert!(batch_size > 0, "batch size must be greater than zero");
+
+        UrlSetBatches {
+            url_set: self,
+            batch_size: batch_size.min(MAX_URLS_PER_SET),
+        }
+    }
+
+    /// Builds a GET request for a single URL or a JSON POST request otherwise
+    fn request(&self, endpoint: http::Uri) -> Result<http::Request<bytes::Bytes>> {
+        if let [url] = self.url_list.as_slice() {
+            submit_one_request(
+                endpoint,
+                self.key.clone(),
+                self.key_location.clone(),
+                url.clone(),
+            )
+        } else {
+            url_set_request(endpoint, self)
+        }
+    }
+}
+
+/// Iterator over the batches of a [`UrlSet`], see [`UrlSet::batches`]
+#[derive(Debug)]
+pub struct UrlSetBatches {
+    url_set: UrlSet,
+    batch_size: usize,
+}
+
+impl Iterator for UrlSetBatches {
+    type Item = UrlSet;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.url_set.url_list.is_empty() {
+            return None;
+        }
+
+        let at = self.batch_size.min(self.url_set.url_list.len());
+        let remaining = self.url_set.url_list.split_off(at);
+        let url_list = std::mem::replace(&mut self.url_set.url_list, remaining);
+
+        Some(UrlSet {
+            host: self.url_set.host.clone(),
+            key: self.url_set.key.clone(),
+            key_location: self.url_set.key_location.clone(),
+            url_list,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let batches = (self.url_set.url_list.len() + self.batch_size - 1) / self.batch_size;
+        (batches, Some(batches))
+    }
+}
+
+impl ExactSizeIterator for UrlSetBatches {}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UrlSetBody<'a> {
+    host: &'a str,
+    key: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
     key_location: Option<String>,
     url_list: Vec<String>,
 }
@@ -93,38 +903,77 @@ struct UrlSet {
 fn submit_set_request(
     endpoint: http::Uri,
     key: crate::Key,
-    _key_location: Option<http::Uri>,
-    _urls: Vec<http::Uri>,
-) -> Result<
-    http::Request<impl http_body::Body<Data = impl bytes::Buf, Error = std::convert::Infallible>>,
-> {
+    key_location: Option<http::Uri>,
+    urls: Vec<http::Uri>,
+) -> Result<http::Request<crate::transport::Body>> {
+    Ok(
+        url_set_request(endpoint, &UrlSet::new(key, key_location, urls)?)?
+            .map(crate::transport::Body::new),
+    )
+}
+
+fn url_set_request(endpoint: http::Uri, url_set: &UrlSet) -> Result<http::Request<bytes::Bytes>> {
     let request = http::Request::builder()
-        .uri(endpoint)
+        .uri(endpoint.clone())
         .method(http::Method::POST)
         .header(
             http::header::CONTENT_TYPE,
             /*headers::ContentType::json()*/ "application/json",
         );
 
-    let url_set = UrlSet {
-        host: "".to_string(),
-        key: key.0,
-        key_location: None,
-        url_list: vec![],
+    let body = UrlSetBody {
+        host: &url_set.host,
+        key: url_set.key.expose_secret(),
+        key_location: url_set
+            .key_location
+            .as_ref()
+            .map(|key_location| key_location.to_string()),
+        url_list: url_set.url_list.iter().map(http::Uri::to_string).collect(),
     };
 
-    let body =
-        serde_json::to_vec(&url_set).map_err(|e| crate::IndexnowError::Other(Box::new(e)))?;
+    let body = serde_json::to_vec(&body).map_err(|reason| crate::IndexnowError::Serialization {
+        host: url_set.host.clone(),
+        reason,
+    })?;
 
-    Ok(request
-        .body(http_body::Full::new(bytes::Bytes::from(body)))
-        .map_err(|e| crate::IndexnowError::Other(Box::new(e)))?)
+    request
+        .body(bytes::Bytes::from(body))
+        .map_err(|reason| invalid_uri(&endpoint.to_string(), reason))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_key_generate() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        for len in [Key::MIN_LENGTH, 32, Key::MAX_LENGTH] {
+            let key = Key::generate(len)?;
+
+            assert_eq!(key.expose_secret().len(), len);
+            assert!(key.expose_secret().parse::<Key>().is_ok());
+        }
+
+        assert_ne!(Key::generate(32)?, Key::generate(32)?);
+        assert!(Key::generate(Key::MIN_LENGTH - 1).is_err());
+        assert!(Key::generate(Key::MAX_LENGTH + 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_redacted() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let key: Key = "687a308e4eff49f994d89eb22f764514".parse()?;
+
+        assert_eq!(format!("{:?}", key), r#"Key([redacted])"#);
+        assert_eq!(key.to_string(), "[redacted]");
+        assert_eq!(key.expose_secret(), "687a308e4eff49f994d89eb22f764514");
+        assert_eq!(key, "687a308e4eff49f994d89eb22f764514".parse::<Key>()?);
+        assert_ne!(key, "687a308e4eff49f994d89eb22f76451".parse::<Key>()?);
+
+        Ok(())
+    }
+
     #[test]
     fn test_submit_one_request() -> std::result::Result<(), Box<dyn std::error::Error>> {
         let request = submit_one_request(
@@ -183,13 +1032,618 @@ mod tests {
         let body_data = body.data().await.unwrap()?;
         let chunk = body_data.chunk();
         let json_body: serde_json::Value = serde_json::from_slice(chunk).unwrap();
-        assert_json_diff::assert_json_include!(
-            actual: json_body,
-            expected: serde_json::json!({
+        assert_json_diff::assert_json_eq!(
+            json_body,
+            serde_json::json!({
+                "host": "www.example.com",
                 "key": "687a308e4eff49f994d89eb22f764514",
+                "urlList": [
+                    "https://www.example.com/url1",
+                    "https://www.example.com/folder/url2",
+                    "https://www.example.com/url3",
+                ],
             })
         );
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_submit_set_request_with_location(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        use bytes::Buf as _;
+        use http_body::Body as _;
+
+        let request = submit_set_request(
+            DEFAULT_ENDPOINT.clone(),
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            Some("https://www.example.com/myIndexNowKey63638.txt".parse()?),
+            vec![
+                "https://www.example.com/url1".parse()?,
+                "https://www.example.com/url2".parse()?,
+            ],
+        )?;
+
+        let mut body = request.into_body();
+        let body_data = body.data().await.unwrap()?;
+        let json_body: serde_json::Value = serde_json::from_slice(body_data.chunk()).unwrap();
+        assert_json_diff::assert_json_eq!(
+            json_body,
+            serde_json::json!({
+                "host": "www.example.com",
+                "key": "687a308e4eff49f994d89eb22f764514",
+                "keyLocation": "https://www.example.com/myIndexNowKey63638.txt",
+                "urlList": [
+                    "https://www.example.com/url1",
+                    "https://www.example.com/url2",
+                ],
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_one_url() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::with_statuses([200]);
+
+        let report = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec!["https://www.example.com/product.html".parse()?],
+            &SubmitOptions::default(),
+        )
+        .await?;
+
+        assert_eq!(report.batches.len(), 1);
+        assert!(matches!(
+            report.batches[0].result,
+            Ok(SubmissionOutcome::Accepted(_))
+        ));
+
+        let requests = transport.take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(*requests[0].uri(), "https://api.indexnow.org/indexnow?url=https%3A%2F%2Fwww.example.com%2Fproduct.html&key=687a308e4eff49f994d89eb22f764514");
+        assert_eq!(requests[0].method(), http::Method::GET);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_urls() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::with_statuses([202]);
+
+        let report = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec![
+                "https://www.example.com/url1".parse()?,
+                "https://www.example.com/url2".parse()?,
+            ],
+            &SubmitOptions::default(),
+        )
+        .await?;
+
+        assert!(report.is_accepted());
+        assert!(matches!(
+            report.batches[0].result,
+            Ok(SubmissionOutcome::AcceptedPendingKeyValidation(_))
+        ));
+
+        let requests = transport.take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(*requests[0].uri(), "https://api.indexnow.org/indexnow");
+        assert_eq!(requests[0].method(), http::Method::POST);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_url_set_batches() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let urls = (1..=5)
+            .map(|i| format!("https://www.example.com/url{}", i).parse())
+            .collect::<std::result::Result<Vec<http::Uri>, _>>()?;
+        let url_set = UrlSet::new("687a308e4eff49f994d89eb22f764514".parse()?, None, urls)?;
+
+        let batches = url_set.batches(2);
+        assert_eq!(batches.len(), 3);
+
+        let batches: Vec<_> = batches.collect();
+        assert_eq!(
+            batches.iter().map(UrlSet::len).collect::<Vec<_>>(),
+            [2, 2, 1]
+        );
+        assert_eq!(batches[2].urls()[0], "https://www.example.com/url5");
+        assert!(batches
+            .iter()
+            .all(|batch| batch.host() == "www.example.com"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_chunked() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::with_statuses([200, 429]);
+
+        let urls = (0..=MAX_URLS_PER_SET)
+            .map(|i| format!("https://www.example.com/url{}", i).parse())
+            .collect::<std::result::Result<Vec<SubmissionUrl>, _>>()?;
+
+        let report = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            urls,
+            &SubmitOptions::default(),
+        )
+        .await?;
+
+        assert_eq!(report.batches.len(), 2);
+        assert_eq!(report.batches[0].url_count(), MAX_URLS_PER_SET);
+        assert_eq!(report.batches[1].url_count(), 1);
+        assert_eq!(report.url_count(), MAX_URLS_PER_SET + 1);
+        assert_eq!(report.accepted_url_count(), MAX_URLS_PER_SET);
+        assert!(!report.is_accepted());
+
+        let requests = transport.take_requests();
+        assert_eq!(requests[0].method(), http::Method::POST);
+        assert_eq!(requests[1].method(), http::Method::GET);
+
+        Ok(())
+    }
+
+    fn mixed_host_urls() -> std::result::Result<Vec<SubmissionUrl>, SubmissionUrlError> {
+        [
+            "https://www.example.com/url1",
+            "https://shop.example.com/url2",
+            "https://www.example.com/url3",
+            "https://blog.example.com/url4",
+        ]
+        .into_iter()
+        .map(str::parse)
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn test_submit_mixed_hosts_split() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let transport = crate::testing::ScriptedTransport::with_statuses([200, 200, 200]);
+
+        let report = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            mixed_host_urls()?,
+            &SubmitOptions::default(),
+        )
+        .await?;
+
+        assert_eq!(
+            report
+                .batches
+                .iter()
+                .map(|batch| (batch.host.as_str(), batch.url_count()))
+                .collect::<Vec<_>>(),
+            [
+                ("www.example.com", 2),
+                ("shop.example.com", 1),
+                ("blog.example.com", 1)
+            ]
+        );
+        assert!(report.skipped_urls.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_mixed_hosts_reject() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let transport = crate::testing::ScriptedTransport::with_statuses(Vec::<u16>::new());
+
+        let result = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            mixed_host_urls()?,
+            &SubmitOptions {
+                host_policy: HostPolicy::Reject,
+                ..SubmitOptions::default()
+            },
+        )
+        .await;
+
+        match result {
+            Err(IndexnowError::MixedHosts(hosts)) => assert_eq!(
+                hosts,
+                ["www.example.com", "shop.example.com", "blog.example.com"]
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(transport.take_requests().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_mixed_hosts_filter() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let transport = crate::testing::ScriptedTransport::with_statuses([200]);
+
+        let report = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            mixed_host_urls()?,
+            &SubmitOptions {
+                host_policy: HostPolicy::Filter("www.example.com".to_string()),
+                ..SubmitOptions::default()
+            },
+        )
+        .await?;
+
+        assert_eq!(report.batches.len(), 1);
+        assert_eq!(report.batches[0].url_count(), 2);
+        assert_eq!(report.skipped_urls.len(), 2);
+
+        let (groups, skipped) =
+            HostPolicy::Filter("www.example.com:8080".to_string()).group(vec![
+                "https://www.example.com:8080/url1".parse()?,
+                "https://www.example.com/url2".parse()?,
+            ])?;
+        assert_eq!(groups, [["https://www.example.com:8080/url1"]]);
+        assert_eq!(skipped, ["https://www.example.com/url2"]);
+
+        let result = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            mixed_host_urls()?,
+            &SubmitOptions {
+                host_policy: HostPolicy::Filter("www.exmaple.com".to_string()),
+                ..SubmitOptions::default()
+            },
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(IndexnowError::NoMatchingHost { skipped: 4, .. })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_submit_retry() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::new([
+            http::Response::builder()
+                .status(429)
+                .header(http::header::RETRY_AFTER, "30")
+                .body(bytes::Bytes::new())?,
+            crate::testing::response(503, ""),
+            crate::testing::response(200, ""),
+        ]);
+
+        let started = tokio::time::Instant::now();
+        let report = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec!["https://www.example.com/product.html".parse()?],
+            &SubmitOptions {
+                retry: RetryPolicy {
+                    max_attempts: 3,
+                    base_delay: std::time::Duration::from_secs(1),
+                    max_delay: std::time::Duration::from_secs(60),
+                    jitter: false,
+                },
+                ..SubmitOptions::default()
+            },
+        )
+        .await?;
+
+        assert!(report.is_accepted());
+        assert_eq!(report.batches[0].attempts, 3);
+        assert_eq!(transport.take_requests().len(), 3);
+        assert!(started.elapsed() >= std::time::Duration::from_secs(32));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "hyper-rustls")]
+    #[tokio::test]
+    async fn test_submit_retry_loopback() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        use crate::testing::raw_response;
+
+        let server = crate::testing::LoopbackServer::start(vec![
+            raw_response(429, &[("Retry-After", "1")], ""),
+            raw_response(503, &[], "Service Unavailable"),
+            raw_response(200, &[], "ok"),
+        ])
+        .await?;
+
+        let started = std::time::Instant::now();
+        let report = submit(
+            &crate::HyperTransport::new(),
+            &[server.url("/indexnow")],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec!["https://www.example.com/product.html".parse()?],
+            &SubmitOptions {
+                retry: RetryPolicy {
+                    max_attempts: 3,
+                    base_delay: std::time::Duration::from_millis(10),
+                    max_delay: std::time::Duration::from_secs(60),
+                    jitter: false,
+                },
+                ..SubmitOptions::default()
+            },
+        )
+        .await?;
+
+        assert_eq!(report.batches[0].attempts, 3);
+        match &report.batches[0].result {
+            Ok(outcome @ SubmissionOutcome::Accepted(_)) => assert_eq!(outcome.body(), "ok"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(started.elapsed() >= std::time::Duration::from_secs(1));
+
+        let requests = server.requests().await?;
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with(
+            "GET /indexnow?url=https%3A%2F%2Fwww.example.com%2Fproduct.html&key=687a308e4eff49f994d89eb22f764514 HTTP/1.1\r\n"
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "hyper-rustls")]
+    #[tokio::test]
+    async fn test_submit_connection_error_loopback(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        // Nothing listens on the port once the listener is dropped
+        let port = std::net::TcpListener::bind("127.0.0.1:0")?
+            .local_addr()?
+            .port();
+        let endpoint: http::Uri = format!("http://127.0.0.1:{}/indexnow", port).parse()?;
+
+        let report = submit(
+            &crate::HyperTransport::new(),
+            &[endpoint],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec!["https://www.example.com/product.html".parse()?],
+            &SubmitOptions::default(),
+        )
+        .await?;
+
+        match &report.batches[0].result {
+            Err(IndexnowError::Batch { reason, .. }) => match &**reason {
+                IndexnowError::Transport { url, .. } => {
+                    assert_eq!(*url, format!("http://127.0.0.1:{}/indexnow", port).as_str())
+                }
+                other => panic!("unexpected reason: {:?}", other),
+            },
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let error = report.batches[0]
+            .summary()
+            .error
+            .expect("transport error in summary");
+        assert!(!error.contains("687a308e4eff49f994d89eb22f764514"));
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_submit_retry_exhausted() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::with_statuses([500, 500]);
+
+        let report = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec!["https://www.example.com/product.html".parse()?],
+            &SubmitOptions {
+                retry: RetryPolicy::with_retries(1),
+                ..SubmitOptions::default()
+            },
+        )
+        .await?;
+
+        assert_eq!(report.batches[0].attempts, 2);
+        assert!(matches!(
+            report.batches[0].result,
+            Ok(SubmissionOutcome::UnexpectedStatus(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_submit_timeout() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let report = submit(
+            &crate::testing::PendingTransport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec!["https://www.example.com/product.html".parse()?],
+            &SubmitOptions {
+                timeout: Some(std::time::Duration::from_secs(10)),
+                ..SubmitOptions::default()
+            },
+        )
+        .await?;
+
+        match &report.batches[0].result {
+            Err(IndexnowError::Batch {
+                host,
+                index,
+                reason,
+                ..
+            }) => {
+                assert_eq!(host, "www.example.com");
+                assert_eq!(*index, 0);
+                assert!(matches!(**reason, IndexnowError::Timeout { .. }));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(
+            report.batches[0].summary().error.as_deref(),
+            Some("Batch 1 of www.example.com to https://api.indexnow.org/indexnow failed: Request to https://api.indexnow.org/indexnow timed out after 10s")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_engines() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::with_statuses([200, 200]);
+
+        let endpoints = [
+            Engine::find("bing").unwrap().endpoint(),
+            Engine::find("yandex").unwrap().endpoint(),
+        ];
+
+        let report = submit(
+            &transport,
+            &endpoints,
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec!["https://www.example.com/product.html".parse()?],
+            &SubmitOptions::default(),
+        )
+        .await?;
+
+        assert!(report.is_accepted());
+        for endpoint in &endpoints {
+            assert_eq!(report.batches_for(endpoint).count(), 1);
+        }
+
+        let mut requested_hosts: Vec<_> = transport
+            .take_requests()
+            .iter()
+            .map(|request| request.uri().host().unwrap().to_string())
+            .collect();
+        requested_hosts.sort();
+        assert_eq!(requested_hosts, ["www.bing.com", "yandex.com"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_out_of_scope() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::with_statuses(Vec::<u16>::new());
+
+        let result = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            Some("https://www.example.com/catalog/key.txt".parse()?),
+            vec![
+                "https://www.example.com/catalog/item1.html".parse()?,
+                "https://www.example.com/item2.html".parse()?,
+            ],
+            &SubmitOptions::default(),
+        )
+        .await;
+
+        assert!(matches!(result, Err(IndexnowError::OutOfScope { .. })));
+        assert!(transport.take_requests().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_ledger() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join(format!(
+            "indexnow-submit-ledger-{}.ndjson",
+            std::process::id()
+        ));
+        let options = SubmitOptions {
+            ledger: Some(Ledger::new(&path)),
+            skip_accepted_within: Some(std::time::Duration::from_secs(3600)),
+            ..SubmitOptions::default()
+        };
+        let transport = crate::testing::ScriptedTransport::with_statuses([200, 200]);
+
+        let report = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec!["https://www.example.com/url1".parse()?],
+            &options,
+        )
+        .await?;
+        assert!(report.skipped_urls.is_empty());
+
+        let report = submit(
+            &transport,
+            &[DEFAULT_ENDPOINT.clone()],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec![
+                "https://www.example.com/url1".parse()?,
+                "https://www.example.com/url2".parse()?,
+            ],
+            &options,
+        )
+        .await?;
+        assert_eq!(report.skipped_urls, ["https://www.example.com/url1"]);
+        assert_eq!(report.batches[0].urls, ["https://www.example.com/url2"]);
+
+        let records = Ledger::new(&path).records()?;
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| (record.url.as_str(), record.outcome.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("https://www.example.com/url1", "accepted"),
+                ("https://www.example.com/url2", "accepted")
+            ]
+        );
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_requests() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let requests = build_requests(
+            &[
+                DEFAULT_ENDPOINT.clone(),
+                crate::Engine::find("bing").unwrap().endpoint(),
+            ],
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            mixed_host_urls()?,
+            &SubmitOptions::default(),
+        )?;
+
+        assert_eq!(requests.len(), 6);
+        assert_eq!(
+            requests
+                .iter()
+                .map(|request| request.method().clone())
+                .collect::<Vec<_>>(),
+            [http::Method::POST, http::Method::GET, http::Method::GET].repeat(2)
+        );
+        assert_eq!(*requests[0].uri(), "https://api.indexnow.org/indexnow");
+        assert_eq!(*requests[1].uri(), "https://api.indexnow.org/indexnow?url=https%3A%2F%2Fshop.example.com%2Furl2&key=687a308e4eff49f994d89eb22f764514");
+        assert_eq!(*requests[3].uri(), "https://www.bing.com/indexnow");
+
+        Ok(())
+    }
 }
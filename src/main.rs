@@ -1,8 +1,19 @@
-use clap::Parser as _;
+use clap::{CommandFactory as _, FromArgMatches as _};
 
 /// Submit changed URLs for search engines to crawl using `IndexNow.org` API
 #[derive(Debug, clap::Parser)]
 struct CliArguments {
+    /// Configuration file with site profiles, defaults to `indexnow/indexnow.toml` in the XDG
+    /// configuration directory
+    #[clap(
+        long,
+        global = true,
+        env = "INDEXNOW_CONFIG",
+        value_name = "PATH",
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    config: Option<std::path::PathBuf>,
+
     #[clap(subcommand)]
     command: CliCommands,
 }
@@ -11,25 +22,58 @@ struct CliArguments {
 enum CliCommands {
     /// Submit URLs for search engines to crawl
     Submit {
+        /// Site profile of the configuration file to take defaults from
+        ///
+        /// Arguments override the site profile, which overrides the `INDEXNOW_KEY`,
+        /// `INDEXNOW_KEY_LOCATION`, `INDEXNOW_ENDPOINT` and `INDEXNOW_LEDGER` environment
+        /// variables.
+        #[clap(long, env = "INDEXNOW_SITE", value_name = "NAME")]
+        site: Option<String>,
+
         /// Key to verify ownership of submitted URLs
         #[clap(
             short = 'k',
             long = "key",
             env = "INDEXNOW_KEY",
             hide_env_values = true,
+            required_unless_present = "site",
             value_hint = clap::ValueHint::Other,
         )]
-        key: indexnow::Key,
+        key: Option<indexnow::Key>,
 
         /// Changed URLs for search engines to crawl
         #[clap(
             value_name = "URL",
-            required = true,
+            required_unless_present_any = &["sitemap", "feed", "urls-file", "site"],
             use_value_delimiter = false,
-            max_values = 10_000,
             value_hint = clap::ValueHint::Url,
         )]
-        urls: Vec<http::Uri>,
+        urls: Vec<indexnow::SubmissionUrl>,
+
+        /// File with one URL per line to submit, or `-` to read from stdin
+        ///
+        /// Unless combined with other URLs, a sitemap, a feed or `--dry-run`, the URLs are
+        /// submitted while the file is read, so URLs before an invalid line may be submitted.
+        #[clap(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+        urls_file: Option<std::path::PathBuf>,
+
+        /// Sitemap or sitemap index to submit the URLs of, as file path or URL
+        #[clap(long, value_name = "PATH|URL", value_hint = clap::ValueHint::AnyPath)]
+        sitemap: Option<indexnow::Source>,
+
+        /// State file to only submit sitemap URLs that changed since the last run, requires a
+        /// sitemap
+        #[clap(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+        state: Option<std::path::PathBuf>,
+
+        /// RSS or Atom feed to submit the links of, as file path or URL
+        #[clap(long, value_name = "PATH|URL", value_hint = clap::ValueHint::AnyPath)]
+        feed: Option<indexnow::Source>,
+
+        /// Only submit feed links published or updated since this RFC 3339 timestamp, requires a
+        /// feed
+        #[clap(long, value_name = "TIMESTAMP", value_parser = parse_timestamp)]
+        since: Option<time::OffsetDateTime>,
 
         /// URL of the key file
         #[clap(
@@ -47,44 +91,775 @@ enum CliCommands {
             env = "INDEXNOW_ENDPOINT",
             value_hint = clap::ValueHint::Url,
         )]
-        endpoint: Option<http::Uri>,
+        endpoint: Vec<http::Uri>,
+
+        /// Name of a known `IndexNow.org` search engine
+        #[clap(
+            long,
+            value_name = "NAME",
+            possible_values = indexnow::ENGINES.iter().map(|engine| engine.name),
+        )]
+        engine: Vec<String>,
+
+        /// Fail instead of submitting URLs of multiple hosts separately
+        #[clap(long, conflicts_with = "only-host")]
+        reject_mixed_hosts: bool,
+
+        /// Only submit URLs of this host, or host and port, and skip all others
+        #[clap(long, value_name = "HOST", value_hint = clap::ValueHint::Hostname)]
+        only_host: Option<String>,
+
+        /// Number of retries for throttled or failed submissions [default: 0]
+        #[clap(long, value_name = "N")]
+        retries: Option<u32>,
+
+        /// Maximum delay in seconds between retries [default: 60]
+        #[clap(long, value_name = "SECONDS")]
+        max_backoff: Option<u64>,
+
+        /// Timeout in seconds of each request, including fetching sitemaps and feeds
+        #[clap(long, value_name = "SECONDS")]
+        timeout: Option<u64>,
+
+        /// Ledger file to record submissions in
+        #[clap(
+            long,
+            env = "INDEXNOW_LEDGER",
+            value_name = "PATH",
+            value_hint = clap::ValueHint::FilePath,
+        )]
+        ledger: Option<std::path::PathBuf>,
+
+        /// Skip URLs accepted within this many seconds according to the ledger, requires a ledger
+        #[clap(long, value_name = "SECONDS")]
+        skip_accepted_within: Option<u64>,
+
+        /// Print the requests as `curl` commands, raw HTTP or JSON instead of sending them
+        #[clap(
+            long,
+            value_name = "FORMAT",
+            min_values = 0,
+            require_equals = true,
+            default_missing_value = "curl",
+            possible_values = ["curl", "http", "json"],
+        )]
+        dry_run: Option<indexnow::RequestFormat>,
+
+        /// Show the key in the requests printed by `--dry-run` instead of redacting it
+        #[clap(long, requires = "dry-run")]
+        show_key: bool,
+
+        /// Format of the submission report
+        #[clap(long, value_name = "FORMAT", value_enum, default_value = "text")]
+        output: OutputFormat,
+    },
+
+    /// Show submissions recorded in the ledger
+    History {
+        /// Ledger file submissions were recorded in
+        #[clap(
+            long,
+            env = "INDEXNOW_LEDGER",
+            value_name = "PATH",
+            value_hint = clap::ValueHint::FilePath,
+        )]
+        ledger: std::path::PathBuf,
+
+        /// Only show submissions of this URL
+        #[clap(long, value_name = "URL", value_hint = clap::ValueHint::Url)]
+        url: Option<String>,
+
+        /// Maximum number of most recent submissions to show
+        #[clap(short = 'n', long, value_name = "N")]
+        limit: Option<usize>,
+    },
+
+    /// Show statistics per endpoint of the submissions recorded in the ledger
+    Stats {
+        /// Ledger file submissions were recorded in
+        #[clap(
+            long,
+            env = "INDEXNOW_LEDGER",
+            value_name = "PATH",
+            value_hint = clap::ValueHint::FilePath,
+        )]
+        ledger: std::path::PathBuf,
+    },
+
+    /// Verify that the key file is served correctly
+    Verify {
+        /// Key to verify ownership of submitted URLs
+        #[clap(
+            short = 'k',
+            long = "key",
+            env = "INDEXNOW_KEY",
+            hide_env_values = true,
+            value_hint = clap::ValueHint::Other,
+        )]
+        key: indexnow::Key,
+
+        /// Host serving the key file at its root, unless a key location is given
+        #[clap(
+            value_name = "HOST",
+            required_unless_present = "key-location",
+            value_hint = clap::ValueHint::Hostname,
+        )]
+        host: Option<http::Uri>,
+
+        /// URL of the key file
+        #[clap(
+            short = 'l',
+            long,
+            env = "INDEXNOW_KEY_LOCATION",
+            value_hint = clap::ValueHint::Url,
+        )]
+        key_location: Option<http::Uri>,
+    },
+
+    /// Generate a new random key
+    Keygen {
+        /// Number of characters of the key
+        #[clap(short = 'n', long, default_value_t = 32)]
+        length: usize,
+
+        /// Web root directory to write the `{key}.txt` key file into
+        #[clap(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+        web_root: Option<std::path::PathBuf>,
     },
 }
 
-#[derive(Debug)]
+/// Format of the submission report
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum OutputFormat {
+    /// One line per batch and per endpoint
+    Text,
+
+    /// Single JSON object with all batches and endpoints
+    Json,
+
+    /// One JSON object per line for each batch and endpoint
+    Ndjson,
+}
+
+fn parse_timestamp(s: &str) -> Result<time::OffsetDateTime, time::error::Parse> {
+    time::OffsetDateTime::parse(s, &time::format_description::well_known::Rfc3339)
+}
+
+#[derive(Debug, thiserror::Error)]
 enum IndexnowCliError {
-    Indexnow,
+    #[error(transparent)]
+    Indexnow(#[from] indexnow::IndexnowError),
+
+    #[error("Failed to access {path}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to read arguments")]
+    Arguments(#[source] std::io::Error),
+
+    #[error(transparent)]
+    Config(#[from] indexnow::ConfigError),
+
+    #[error("Unknown search engine {0:?}")]
+    UnknownEngine(String),
+
+    #[error("Failed to format timestamp")]
+    Timestamp(#[from] time::error::Format),
+}
+
+// Exit codes as per `sysexits.h`
+const USAGE: u8 = 64;
+const DATA: u8 = 65;
+const UNAVAILABLE: u8 = 69;
+const SOFTWARE: u8 = 70;
+const IO: u8 = 74;
+const TEMPFAIL: u8 = 75;
+const CONFIG: u8 = 78;
+
+impl IndexnowCliError {
+    /// Exit code of the error class, as per `sysexits.h`
+    fn exit_code(&self) -> u8 {
+        match self {
+            Self::Indexnow(error) => indexnow_exit_code(error),
+            Self::Io { .. } | Self::Arguments(_) => IO,
+            Self::Config(_) => CONFIG,
+            Self::UnknownEngine(_) => USAGE,
+            Self::Timestamp(_) => SOFTWARE,
+        }
+    }
+}
+
+/// Exit code of the class of the library error
+fn indexnow_exit_code(error: &indexnow::IndexnowError) -> u8 {
+    use indexnow::IndexnowError as E;
+
+    match error {
+        E::InvalidKey
+        | E::NoUrls
+        | E::MissingKey
+        | E::MissingHost
+        | E::InvalidUrl { .. }
+        | E::NoMatchingHost { .. }
+        | E::MixedHosts(_)
+        | E::OutOfScope { .. }
+        | E::InvalidSitemap { .. }
+        | E::InvalidFeed { .. }
+        | E::InvalidUrlList { .. }
+        | E::InvalidKeyFile { .. } => DATA,
+        E::NoEndpoints | E::Config { .. } => CONFIG,
+        E::Transport { .. } | E::Timeout { .. } => UNAVAILABLE,
+        E::Batch { reason, .. } | E::Interrupted { reason, .. } => indexnow_exit_code(reason),
+        E::Read {
+            reason: indexnow::ReadError::Io(_),
+            ..
+        }
+        | E::Ledger { .. }
+        | E::State { .. } => IO,
+        E::Read { .. } => UNAVAILABLE,
+        E::MissingTransport
+        | E::UrlEncoding { .. }
+        | E::InvalidUri { .. }
+        | E::Serialization { .. } => SOFTWARE,
+    }
+}
+
+/// Exit code of the first batch of the report that was not accepted, if any
+///
+/// Failed batches are reported instead of failing the submission, so their class is derived from
+/// the error of the batch or the status of its response.
+fn report_exit_code(report: &indexnow::SubmissionReport) -> Option<u8> {
+    use indexnow::SubmissionOutcome as O;
+
+    let batch = report.batches.iter().find(|batch| !batch.is_accepted())?;
+
+    Some(match &batch.result {
+        Err(error) => indexnow_exit_code(error),
+        Ok(O::TooManyRequests(_)) => TEMPFAIL,
+        Ok(outcome) if outcome.status().is_client_error() => DATA,
+        Ok(_) => UNAVAILABLE,
+    })
 }
 
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> Result<std::process::ExitCode, crate::IndexnowCliError> {
-    let args = argfile::expand_args(argfile::parse_fromfile, argfile::PREFIX).unwrap();
-    let cli = CliArguments::parse_from(args);
-    println!("{:?}", cli);
+async fn main() -> std::process::ExitCode {
+    match run().await {
+        Ok(exit_code) => exit_code,
+        Err(error) => {
+            eprintln!("error: {}", error);
+
+            use std::error::Error as _;
+
+            let mut source = error.source();
+            while let Some(error) = source {
+                eprintln!("  caused by: {}", error);
+                source = error.source();
+            }
+
+            std::process::ExitCode::from(error.exit_code())
+        }
+    }
+}
+
+async fn run() -> Result<std::process::ExitCode, crate::IndexnowCliError> {
+    let args = argfile::expand_args(argfile::parse_fromfile, argfile::PREFIX)
+        .map_err(crate::IndexnowCliError::Arguments)?;
+    let matches = CliArguments::command().get_matches_from(args);
+    let cli = CliArguments::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
 
     match cli.command {
         CliCommands::Submit {
-            endpoint,
+            site,
+            mut endpoint,
+            mut engine,
             key,
             key_location,
-            urls,
+            mut urls,
+            urls_file,
+            sitemap,
+            state,
+            feed,
+            since,
+            mut reject_mixed_hosts,
+            mut only_host,
+            retries,
+            max_backoff,
+            timeout,
+            ledger,
+            skip_accepted_within,
+            dry_run,
+            show_key,
+            output,
         } => {
-            indexnow::submit(
-                endpoint.unwrap_or(indexnow::DEFAULT_ENDPOINT.clone()),
-                key,
-                key_location,
-                urls,
-            )
-            .await
-            .map_err(|_| crate::IndexnowCliError::Indexnow)?;
+            let profile = match &site {
+                Some(site) => load_profile(cli.config.as_deref(), site)?,
+                None => indexnow::SiteProfile::default(),
+            };
+
+            // Explicit arguments override the site profile, which overrides environment variables
+            let matches = matches
+                .subcommand_matches("submit")
+                .expect("matches of the submit subcommand");
+            let (key, env_key) = split_env(matches, "key", key);
+            let (key_location, env_key_location) = split_env(matches, "key-location", key_location);
+            let (ledger, env_ledger) = split_env(matches, "ledger", ledger);
+            let env_endpoint = if is_from_env(matches, "endpoint") {
+                std::mem::take(&mut endpoint)
+            } else {
+                vec![]
+            };
+
+            let key = match key.or(profile.key).or(env_key) {
+                Some(key) => key,
+                None => missing_argument("No key given as argument nor in the site profile"),
+            };
+            let key_location = key_location.or(profile.key_location).or(env_key_location);
+            if endpoint.is_empty() && engine.is_empty() {
+                endpoint = profile.endpoints;
+                engine = profile.engines;
+            }
+            if endpoint.is_empty() && engine.is_empty() {
+                endpoint = env_endpoint;
+            }
+            let sitemap = sitemap.or(profile.sitemap);
+            let state = state.or(profile.state);
+            let feed = feed.or(profile.feed);
+            if only_host.is_none() && !reject_mixed_hosts {
+                only_host = profile.only_host;
+                reject_mixed_hosts = profile.reject_mixed_hosts;
+            }
+            let retries = retries.or(profile.retries).unwrap_or(0);
+            let max_backoff = max_backoff.or(profile.max_backoff).unwrap_or(60);
+            let ledger = ledger.or(profile.ledger).or(env_ledger);
+
+            // Only checked now, as the site profile may give what the arguments require
+            if state.is_some() && sitemap.is_none() {
+                missing_argument("--state requires a sitemap as argument or in the site profile");
+            }
+            if since.is_some() && feed.is_none() {
+                missing_argument("--since requires a feed as argument or in the site profile");
+            }
+            if skip_accepted_within.is_some() && ledger.is_none() {
+                missing_argument(
+                    "--skip-accepted-within requires a ledger as argument or in the site profile",
+                );
+            }
+
+            // Also limits fetching sitemaps, feeds and pages to hash, not only submissions
+            let transport = std::sync::Arc::new(match timeout {
+                Some(timeout) => {
+                    indexnow::HyperTransport::with_timeout(std::time::Duration::from_secs(timeout))
+                }
+                None => indexnow::HyperTransport::new(),
+            });
+
+            let mut url_list = None;
+            if let Some(urls_file) = urls_file {
+                let reader: Box<dyn std::io::BufRead> = if urls_file.as_os_str() == "-" {
+                    Box::new(std::io::stdin().lock())
+                } else {
+                    Box::new(std::io::BufReader::new(
+                        std::fs::File::open(&urls_file).map_err(|source| {
+                            crate::IndexnowCliError::Io {
+                                path: urls_file.display().to_string(),
+                                source,
+                            }
+                        })?,
+                    ))
+                };
+
+                let list = indexnow::UrlList::new(reader, urls_file.display().to_string());
+
+                // Only the URLs of a list on its own can be submitted without collecting them, as
+                // other URLs are merged with them and requests are built from all of them at once
+                if urls.is_empty() && sitemap.is_none() && feed.is_none() && dry_run.is_none() {
+                    url_list = Some(list);
+                } else {
+                    for url in list {
+                        urls.push(url?);
+                    }
+                }
+            }
+
+            let mut sitemap_changes = None;
+            if let Some(sitemap) = sitemap {
+                let limits = indexnow::SitemapLimits::default();
+                let entries = indexnow::read_sitemap(&transport, &sitemap, &limits).await?;
+
+                match &state {
+                    Some(state) => {
+                        let sitemap_state = indexnow::SitemapState::load(state)?;
+                        let changes = sitemap_state
+                            .changes(&transport, &entries, limits.max_size)
+                            .await?;
+                        urls.extend(submission_urls(changes.urls())?);
+                        sitemap_changes = Some((sitemap_state, changes));
+                    }
+                    None => {
+                        urls.extend(submission_urls(entries.into_iter().map(|entry| entry.loc))?)
+                    }
+                }
+            }
+
+            if let Some(feed) = feed {
+                let entries = indexnow::read_feed(
+                    &transport,
+                    &feed,
+                    &indexnow::FeedOptions {
+                        since,
+                        ..indexnow::FeedOptions::default()
+                    },
+                )
+                .await?;
+                urls.extend(submission_urls(
+                    entries.into_iter().map(|entry| entry.link),
+                )?);
+            }
+
+            if urls.is_empty() && url_list.is_none() {
+                print_no_urls(output);
+                return Ok(std::process::ExitCode::SUCCESS);
+            }
+
+            let mut builder = indexnow::IndexnowClient::builder()
+                .transport(transport.clone())
+                .key(key)
+                .endpoints(endpoint);
+            for name in &engine {
+                match indexnow::Engine::find(name) {
+                    Some(engine) => builder = builder.engine(engine),
+                    None => return Err(crate::IndexnowCliError::UnknownEngine(name.clone())),
+                }
+            }
+            if let Some(key_location) = key_location {
+                builder = builder.key_location(key_location);
+            }
+
+            builder = builder.host_policy(match only_host {
+                Some(host) => indexnow::HostPolicy::Filter(host),
+                None if reject_mixed_hosts => indexnow::HostPolicy::Reject,
+                None => indexnow::HostPolicy::Split,
+            });
+
+            let max_delay = std::time::Duration::from_secs(max_backoff);
+            let default_retry = indexnow::RetryPolicy::with_retries(retries);
+            builder = builder.retry(indexnow::RetryPolicy {
+                base_delay: default_retry.base_delay.min(max_delay),
+                max_delay,
+                ..default_retry
+            });
+
+            if let Some(timeout) = timeout {
+                builder = builder.timeout(std::time::Duration::from_secs(timeout));
+            }
+            if let Some(ledger) = ledger {
+                builder = builder.ledger(indexnow::Ledger::new(ledger));
+            }
+            if let Some(window) = skip_accepted_within {
+                builder = builder.skip_accepted_within(std::time::Duration::from_secs(window));
+            }
+
+            let client = builder.build()?;
+
+            if let Some(format) = dry_run {
+                let requests = client.build_requests(urls)?;
+                let rendered = indexnow::render_requests(&requests, format);
+
+                if show_key {
+                    println!("{}", rendered);
+                } else {
+                    let key = client.key();
+                    println!(
+                        "{}",
+                        rendered.replace(key.expose_secret(), &key.to_string())
+                    );
+                }
+                return Ok(std::process::ExitCode::SUCCESS);
+            }
+
+            let submitted = match url_list {
+                Some(url_list) => submit_url_list(&client, url_list).await,
+                None => client.submit_urls(urls).await,
+            };
+            let report = match submitted {
+                Ok(report) => report,
+                Err(indexnow::IndexnowError::NoUrls) => {
+                    print_no_urls(output);
+                    return Ok(std::process::ExitCode::SUCCESS);
+                }
+                Err(error) => {
+                    if let indexnow::IndexnowError::Interrupted { report, .. } = &error {
+                        print_report(report, output);
+                    }
+                    return Err(error.into());
+                }
+            };
+            print_report(&report, output);
+
+            if let (Some(state), Some((mut sitemap_state, changes))) = (state, sitemap_changes) {
+                sitemap_state.apply(&changes, &report.accepted_urls());
+                sitemap_state.save(&state)?;
+            }
+
+            if let Some(exit_code) = report_exit_code(&report) {
+                return Ok(std::process::ExitCode::from(exit_code));
+            }
+        }
+        CliCommands::History { ledger, url, limit } => {
+            let records = indexnow::Ledger::new(ledger).records()?;
+
+            let records: Vec<_> = records
+                .into_iter()
+                .filter(|record| url.as_ref().map_or(true, |url| record.url == *url))
+                .collect();
+            let skip = limit.map_or(0, |limit| records.len().saturating_sub(limit));
+
+            for record in &records[skip..] {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    record
+                        .timestamp
+                        .format(&time::format_description::well_known::Rfc3339)?,
+                    record.endpoint,
+                    record.url,
+                    record
+                        .status
+                        .map_or("-".to_string(), |status| status.to_string()),
+                    record.outcome,
+                );
+            }
+        }
+        CliCommands::Stats { ledger } => {
+            let records = indexnow::Ledger::new(ledger).records()?;
+
+            for (endpoint, stats) in indexnow::ledger_stats(&records) {
+                println!(
+                    "{}\tsubmissions: {}\taccepted: {}\tURLs: {}\tlast: {}",
+                    endpoint,
+                    stats.submissions,
+                    stats.accepted,
+                    stats.urls,
+                    stats
+                        .last_submission
+                        .map(|last_submission| last_submission
+                            .format(&time::format_description::well_known::Rfc3339))
+                        .transpose()?
+                        .unwrap_or_default(),
+                );
+            }
+        }
+        CliCommands::Verify {
+            key,
+            host,
+            key_location,
+        } => {
+            let transport = indexnow::HyperTransport::new();
+
+            // An explicit key location is fetched as is, the host only implies the default one
+            let key_location_or_host = key_location
+                .or(host)
+                .expect("either host or key location to be required");
+
+            indexnow::verify_key_location(&transport, &key, &key_location_or_host).await?;
+
+            println!("Key file is valid");
+        }
+        CliCommands::Keygen { length, web_root } => {
+            let key = indexnow::Key::generate(length)?;
+
+            if let Some(web_root) = web_root {
+                let path = web_root.join(key.file_name());
+                std::fs::write(&path, key.expose_secret()).map_err(|source| {
+                    crate::IndexnowCliError::Io {
+                        path: path.display().to_string(),
+                        source,
+                    }
+                })?;
+            }
+
+            println!("{}", key.expose_secret());
         }
     }
 
     Ok(std::process::ExitCode::SUCCESS)
 }
 
+/// Submits the URLs of the list as they are read, stopping at the first invalid line
+async fn submit_url_list<R: std::io::BufRead>(
+    client: &indexnow::IndexnowClient,
+    url_list: indexnow::UrlList<R>,
+) -> Result<indexnow::SubmissionReport, indexnow::IndexnowError> {
+    let mut list_error = None;
+    let urls = url_list.map_while(|url| url.map_err(|error| list_error = Some(error)).ok());
+
+    let submitted = client.submit_stream(futures_util::stream::iter(urls)).await;
+
+    match (submitted, list_error) {
+        (Ok(report), Some(error)) => Err(indexnow::IndexnowError::Interrupted {
+            report: Box::new(report),
+            reason: Box::new(error),
+        }),
+        (Err(indexnow::IndexnowError::NoUrls), Some(error)) => Err(error),
+        (submitted, _) => submitted,
+    }
+}
+
+fn submission_urls(
+    urls: impl IntoIterator<Item = http::Uri>,
+) -> Result<Vec<indexnow::SubmissionUrl>, crate::IndexnowCliError> {
+    urls.into_iter()
+        .map(|url| {
+            indexnow::SubmissionUrl::try_from(url.clone()).map_err(|reason| {
+                crate::IndexnowCliError::Indexnow(indexnow::IndexnowError::InvalidUrl {
+                    url: url.to_string(),
+                    reason,
+                })
+            })
+        })
+        .collect()
+}
+
+fn load_profile(
+    config: Option<&std::path::Path>,
+    site: &str,
+) -> Result<indexnow::SiteProfile, crate::IndexnowCliError> {
+    let config = match config {
+        Some(config) => indexnow::Config::load(config),
+        None => indexnow::Config::discover(),
+    }?;
+
+    Ok(config.site(site).cloned()?)
+}
+
+/// Exits with a usage error for an argument missing from both arguments and site profile
+fn missing_argument(message: &str) -> ! {
+    CliArguments::command()
+        .error(clap::ErrorKind::MissingRequiredArgument, message)
+        .exit()
+}
+
+/// Whether the argument was only given by its environment variable
+fn is_from_env(matches: &clap::ArgMatches, id: &str) -> bool {
+    matches!(
+        matches.value_source(id),
+        Some(clap::ValueSource::EnvVariable)
+    )
+}
+
+/// Splits the value of the argument into one given explicitly and one from its environment
+/// variable, as the site profile takes precedence over the latter
+fn split_env<T>(matches: &clap::ArgMatches, id: &str, value: Option<T>) -> (Option<T>, Option<T>) {
+    if is_from_env(matches, id) {
+        (None, value)
+    } else {
+        (value, None)
+    }
+}
+
+fn print_no_urls(output: OutputFormat) {
+    // Keep machine-readable output parseable with an empty report
+    match output {
+        OutputFormat::Text => println!("No new or changed URLs to submit"),
+        _ => print_report(&indexnow::SubmissionReport::default(), output),
+    }
+}
+
+fn print_report(report: &indexnow::SubmissionReport, output: OutputFormat) {
+    let batches: Vec<_> = report
+        .batches
+        .iter()
+        .map(indexnow::BatchReport::summary)
+        .collect();
+    let endpoints = report.endpoint_summaries();
+    let skipped_urls: Vec<_> = report
+        .skipped_urls
+        .iter()
+        .map(http::Uri::to_string)
+        .collect();
+
+    match output {
+        OutputFormat::Text => {
+            for batch in &batches {
+                println!(
+                    "{}\t{}\t{} URLs\t{}\t{}\t{} attempts\t{} ms{}",
+                    batch.endpoint,
+                    batch.host,
+                    batch.url_count,
+                    batch
+                        .status
+                        .map_or("-".to_string(), |status| status.to_string()),
+                    batch.outcome,
+                    batch.attempts,
+                    batch.latency_ms,
+                    batch
+                        .error
+                        .as_ref()
+                        .map_or(String::new(), |error| format!("\t{}", error)),
+                );
+            }
+            for endpoint in &endpoints {
+                println!(
+                    "{}\t{} of {} URLs accepted in {} batches",
+                    endpoint.endpoint,
+                    endpoint.accepted_url_count,
+                    endpoint.url_count,
+                    endpoint.batches,
+                );
+            }
+            if !skipped_urls.is_empty() {
+                println!("Skipped {} URLs", skipped_urls.len());
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "accepted": report.is_accepted(),
+                "batches": batches,
+                "endpoints": endpoints,
+                "skipped_urls": skipped_urls,
+            })
+        ),
+        OutputFormat::Ndjson => {
+            for batch in &batches {
+                let mut line = serde_json::json!(batch);
+                line["type"] = "batch".into();
+                println!("{}", line);
+            }
+            for endpoint in &endpoints {
+                let mut line = serde_json::json!(endpoint);
+                line["type"] = "endpoint".into();
+                println!("{}", line);
+            }
+        }
+    }
+}
+
 #[test]
 fn verify_cli() {
     use clap::CommandFactory;
     CliArguments::command().debug_assert()
 }
+
+#[test]
+fn site_profile_overrides_env() {
+    std::env::set_var("INDEXNOW_KEY_LOCATION", "https://www.example.com/env.txt");
+
+    let matches = CliArguments::command().get_matches_from([
+        "indexnow",
+        "submit",
+        "--site",
+        "blog",
+        "--ledger",
+        "ledger.ndjson",
+    ]);
+    let matches = matches.subcommand_matches("submit").unwrap();
+
+    assert!(is_from_env(matches, "key-location"));
+    assert!(!is_from_env(matches, "ledger"));
+    assert_eq!(split_env(matches, "key-location", Some(1)), (None, Some(1)));
+    assert_eq!(split_env(matches, "ledger", Some(2)), (Some(2), None));
+
+    std::env::remove_var("INDEXNOW_KEY_LOCATION");
+}
@@ -0,0 +1,385 @@
+/// Status and body of an `IndexNow.org` API response
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmissionResponse {
+    pub status: http::StatusCode,
+    pub body: bytes::Bytes,
+}
+
+/// Outcome of a submission as defined by the response codes of the `IndexNow.org` protocol
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubmissionOutcome {
+    /// `200 OK`, URLs submitted successfully
+    Accepted(SubmissionResponse),
+
+    /// `202 Accepted`, URLs received but the key is yet to be validated
+    AcceptedPendingKeyValidation(SubmissionResponse),
+
+    /// `400 Bad Request`, invalid format
+    BadRequest(SubmissionResponse),
+
+    /// `403 Forbidden`, key not found or not matching the key file
+    KeyNotValid(SubmissionResponse),
+
+    /// `422 Unprocessable Entity`, URLs not belonging to the host or not matching the protocol
+    UnprocessableUrls(SubmissionResponse),
+
+    /// `429 Too Many Requests`, potential spam
+    TooManyRequests(SubmissionResponse),
+
+    /// Any other status code not defined by the protocol
+    UnexpectedStatus(SubmissionResponse),
+}
+
+impl SubmissionOutcome {
+    pub fn from_response(response: http::Response<bytes::Bytes>) -> Self {
+        let (parts, body) = response.into_parts();
+        let response = SubmissionResponse {
+            status: parts.status,
+            body,
+        };
+
+        match response.status {
+            http::StatusCode::OK => Self::Accepted(response),
+            http::StatusCode::ACCEPTED => Self::AcceptedPendingKeyValidation(response),
+            http::StatusCode::BAD_REQUEST => Self::BadRequest(response),
+            http::StatusCode::FORBIDDEN => Self::KeyNotValid(response),
+            http::StatusCode::UNPROCESSABLE_ENTITY => Self::UnprocessableUrls(response),
+            http::StatusCode::TOO_MANY_REQUESTS => Self::TooManyRequests(response),
+            _ => Self::UnexpectedStatus(response),
+        }
+    }
+
+    pub fn response(&self) -> &SubmissionResponse {
+        match self {
+            Self::Accepted(response)
+            | Self::AcceptedPendingKeyValidation(response)
+            | Self::BadRequest(response)
+            | Self::KeyNotValid(response)
+            | Self::UnprocessableUrls(response)
+            | Self::TooManyRequests(response)
+            | Self::UnexpectedStatus(response) => response,
+        }
+    }
+
+    /// Name of the variant in snake case, e.g. `too_many_requests`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Accepted(_) => "accepted",
+            Self::AcceptedPendingKeyValidation(_) => "accepted_pending_key_validation",
+            Self::BadRequest(_) => "bad_request",
+            Self::KeyNotValid(_) => "key_not_valid",
+            Self::UnprocessableUrls(_) => "unprocessable_urls",
+            Self::TooManyRequests(_) => "too_many_requests",
+            Self::UnexpectedStatus(_) => "unexpected_status",
+        }
+    }
+
+    pub fn status(&self) -> http::StatusCode {
+        self.response().status
+    }
+
+    pub fn body(&self) -> &bytes::Bytes {
+        &self.response().body
+    }
+
+    /// Whether the search engine received the submitted URLs
+    pub fn is_accepted(&self) -> bool {
+        matches!(
+            self,
+            Self::Accepted(_) | Self::AcceptedPendingKeyValidation(_)
+        )
+    }
+}
+
+/// Result of submitting one batch of URLs
+#[derive(Debug)]
+pub struct BatchReport {
+    pub endpoint: http::Uri,
+    pub host: String,
+    pub urls: Vec<http::Uri>,
+
+    /// Number of requests sent, including retries
+    pub attempts: u32,
+
+    /// Time from sending the first request until the final response, including retry delays
+    pub latency: std::time::Duration,
+
+    pub result: crate::Result<SubmissionOutcome>,
+}
+
+impl BatchReport {
+    pub fn url_count(&self) -> usize {
+        self.urls.len()
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        matches!(&self.result, Ok(outcome) if outcome.is_accepted())
+    }
+
+    pub fn summary(&self) -> BatchSummary {
+        let (outcome, status, error) = match &self.result {
+            Ok(outcome) => (
+                outcome.name(),
+                Some(outcome.status().as_u16()),
+                Some(String::from_utf8_lossy(outcome.body()).trim().to_string())
+                    .filter(|body| !outcome.is_accepted() && !body.is_empty()),
+            ),
+            Err(e) => ("error", None, Some(error_chain(e))),
+        };
+
+        BatchSummary {
+            endpoint: self.endpoint.to_string(),
+            host: self.host.clone(),
+            url_count: self.url_count(),
+            status,
+            outcome,
+            attempts: self.attempts,
+            latency_ms: self.latency.as_millis(),
+            error,
+        }
+    }
+}
+
+/// Message of the error followed by those of its sources
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
+    }
+
+    message
+}
+
+/// Serializable summary of a [`BatchReport`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct BatchSummary {
+    pub endpoint: String,
+    pub host: String,
+    pub url_count: usize,
+
+    /// Status of the final response, if any
+    pub status: Option<u16>,
+
+    /// Name of the [`SubmissionOutcome`], or `error` if there was no response
+    pub outcome: &'static str,
+
+    pub attempts: u32,
+    pub latency_ms: u128,
+
+    /// Error, or response body of a submission that was not accepted
+    pub error: Option<String>,
+}
+
+/// Serializable summary of all batches submitted to one endpoint
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct EndpointSummary {
+    pub endpoint: String,
+    pub batches: usize,
+    pub url_count: usize,
+    pub accepted_url_count: usize,
+
+    /// Whether every batch was accepted
+    pub accepted: bool,
+}
+
+/// Aggregated results of all batches of a submission
+#[derive(Debug, Default)]
+pub struct SubmissionReport {
+    pub batches: Vec<BatchReport>,
+
+    /// URLs that were not submitted
+    pub skipped_urls: Vec<http::Uri>,
+}
+
+impl SubmissionReport {
+    /// Whether every batch was accepted
+    pub fn is_accepted(&self) -> bool {
+        self.batches.iter().all(BatchReport::is_accepted)
+    }
+
+    /// Batches submitted to the given endpoint
+    pub fn batches_for<'a>(
+        &'a self,
+        endpoint: &'a http::Uri,
+    ) -> impl Iterator<Item = &'a BatchReport> + 'a {
+        self.batches
+            .iter()
+            .filter(move |batch| batch.endpoint == *endpoint)
+    }
+
+    pub fn url_count(&self) -> usize {
+        self.batches.iter().map(BatchReport::url_count).sum()
+    }
+
+    pub fn accepted_url_count(&self) -> usize {
+        self.batches
+            .iter()
+            .filter(|batch| batch.is_accepted())
+            .map(BatchReport::url_count)
+            .sum()
+    }
+
+    /// Summaries of the batches per endpoint, in the order the endpoints were submitted to
+    pub fn endpoint_summaries(&self) -> Vec<EndpointSummary> {
+        let mut summaries: Vec<EndpointSummary> = vec![];
+
+        for batch in &self.batches {
+            let endpoint = batch.endpoint.to_string();
+            let index = match summaries
+                .iter()
+                .position(|summary| summary.endpoint == endpoint)
+            {
+                Some(index) => index,
+                None => {
+                    summaries.push(EndpointSummary {
+                        endpoint,
+                        batches: 0,
+                        url_count: 0,
+                        accepted_url_count: 0,
+                        accepted: true,
+                    });
+                    summaries.len() - 1
+                }
+            };
+
+            let summary = &mut summaries[index];
+            summary.batches += 1;
+            summary.url_count += batch.url_count();
+            if batch.is_accepted() {
+                summary.accepted_url_count += batch.url_count();
+            } else {
+                summary.accepted = false;
+            }
+        }
+
+        summaries
+    }
+
+    /// URLs accepted by every endpoint they were submitted to
+    pub fn accepted_urls(&self) -> Vec<http::Uri> {
+        let rejected: std::collections::HashSet<_> = self
+            .batches
+            .iter()
+            .filter(|batch| !batch.is_accepted())
+            .flat_map(|batch| &batch.urls)
+            .collect();
+
+        let mut accepted = std::collections::HashSet::new();
+        self.batches
+            .iter()
+            .flat_map(|batch| &batch.urls)
+            .filter(|url| !rejected.contains(url) && accepted.insert(*url))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_response() {
+        for (status, accepted) in [
+            (200, true),
+            (202, true),
+            (400, false),
+            (403, false),
+            (422, false),
+            (429, false),
+            (500, false),
+        ] {
+            let outcome =
+                SubmissionOutcome::from_response(crate::testing::response(status, "body"));
+
+            assert_eq!(outcome.status(), status);
+            assert_eq!(outcome.body(), "body");
+            assert_eq!(outcome.is_accepted(), accepted);
+        }
+
+        assert!(matches!(
+            SubmissionOutcome::from_response(crate::testing::response(403, "")),
+            SubmissionOutcome::KeyNotValid(_)
+        ));
+        assert!(matches!(
+            SubmissionOutcome::from_response(crate::testing::response(422, "")),
+            SubmissionOutcome::UnprocessableUrls(_)
+        ));
+        assert!(matches!(
+            SubmissionOutcome::from_response(crate::testing::response(301, "")),
+            SubmissionOutcome::UnexpectedStatus(_)
+        ));
+    }
+
+    #[test]
+    fn test_summaries() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let batch = |endpoint: &str,
+                     status,
+                     url_count|
+         -> std::result::Result<BatchReport, http::uri::InvalidUri> {
+            Ok(BatchReport {
+                endpoint: endpoint.parse()?,
+                host: "www.example.com".to_string(),
+                urls: (0..url_count)
+                    .map(|i| format!("https://www.example.com/url{}", i).parse())
+                    .collect::<std::result::Result<_, _>>()?,
+                attempts: 1,
+                latency: std::time::Duration::from_millis(120),
+                result: Ok(SubmissionOutcome::from_response(crate::testing::response(
+                    status,
+                    "Too many requests",
+                ))),
+            })
+        };
+
+        let report = SubmissionReport {
+            batches: vec![
+                batch("https://www.bing.com/indexnow", 200, 2)?,
+                batch("https://yandex.com/indexnow", 200, 2)?,
+                batch("https://www.bing.com/indexnow", 429, 1)?,
+            ],
+            skipped_urls: vec![],
+        };
+
+        assert_eq!(
+            report.batches[2].summary(),
+            BatchSummary {
+                endpoint: "https://www.bing.com/indexnow".to_string(),
+                host: "www.example.com".to_string(),
+                url_count: 1,
+                status: Some(429),
+                outcome: "too_many_requests",
+                attempts: 1,
+                latency_ms: 120,
+                error: Some("Too many requests".to_string()),
+            }
+        );
+        assert_eq!(report.batches[0].summary().error, None);
+
+        assert_eq!(
+            report.endpoint_summaries(),
+            [
+                EndpointSummary {
+                    endpoint: "https://www.bing.com/indexnow".to_string(),
+                    batches: 2,
+                    url_count: 3,
+                    accepted_url_count: 2,
+                    accepted: false,
+                },
+                EndpointSummary {
+                    endpoint: "https://yandex.com/indexnow".to_string(),
+                    batches: 1,
+                    url_count: 2,
+                    accepted_url_count: 2,
+                    accepted: true,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
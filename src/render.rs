@@ -0,0 +1,194 @@
+/// Format to render built requests in, see [`render_requests`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequestFormat {
+    /// `curl` command lines
+    Curl,
+
+    /// Raw HTTP/1.1 messages
+    Http,
+
+    /// JSON array of objects with method, URI, headers and body
+    Json,
+}
+
+impl std::str::FromStr for RequestFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "curl" => Ok(Self::Curl),
+            "http" => Ok(Self::Http),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Unknown request format {:?}", s)),
+        }
+    }
+}
+
+/// Renders requests for review before sending them, e.g. those of
+/// [`crate::IndexnowClient::build_requests`]
+pub fn render_requests(requests: &[http::Request<bytes::Bytes>], format: RequestFormat) -> String {
+    match format {
+        RequestFormat::Curl => requests
+            .iter()
+            .map(|request| curl(request) + "\n")
+            .collect(),
+        RequestFormat::Http => requests
+            .iter()
+            .map(http_message)
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        RequestFormat::Json => {
+            let requests: Vec<_> = requests.iter().map(json).collect();
+            serde_json::to_string_pretty(&requests).expect("requests to be serializable")
+        }
+    }
+}
+
+fn curl(request: &http::Request<bytes::Bytes>) -> String {
+    let mut command = format!(
+        "curl -X {} {}",
+        request.method(),
+        shell_quote(&request.uri().to_string())
+    );
+
+    for (name, value) in request.headers() {
+        command.push_str(" -H ");
+        command.push_str(&shell_quote(&format!(
+            "{}: {}",
+            name,
+            String::from_utf8_lossy(value.as_bytes())
+        )));
+    }
+
+    if !request.body().is_empty() {
+        command.push_str(" --data-binary ");
+        command.push_str(&shell_quote(&String::from_utf8_lossy(request.body())));
+    }
+
+    command
+}
+
+fn http_message(request: &http::Request<bytes::Bytes>) -> String {
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map_or("/", http::uri::PathAndQuery::as_str);
+
+    let mut message = format!("{} {} HTTP/1.1\r\n", request.method(), path_and_query);
+
+    if let Some(authority) = request.uri().authority() {
+        message.push_str(&format!("host: {}\r\n", authority));
+    }
+    for (name, value) in request.headers() {
+        message.push_str(&format!(
+            "{}: {}\r\n",
+            name,
+            String::from_utf8_lossy(value.as_bytes())
+        ));
+    }
+    if !request.body().is_empty() {
+        message.push_str(&format!("content-length: {}\r\n", request.body().len()));
+    }
+
+    message.push_str("\r\n");
+    message.push_str(&String::from_utf8_lossy(request.body()));
+
+    message
+}
+
+fn json(request: &http::Request<bytes::Bytes>) -> serde_json::Value {
+    let headers: serde_json::Map<_, _> = request
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into(),
+            )
+        })
+        .collect();
+
+    // Embed JSON bodies as JSON to keep them readable
+    let body = match serde_json::from_slice::<serde_json::Value>(request.body()) {
+        Ok(body) => body,
+        Err(_) if request.body().is_empty() => serde_json::Value::Null,
+        Err(_) => String::from_utf8_lossy(request.body()).into(),
+    };
+
+    serde_json::json!({
+        "method": request.method().as_str(),
+        "uri": request.uri().to_string(),
+        "headers": headers,
+        "body": body,
+    })
+}
+
+/// Quotes for POSIX shells using single quotes
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r#"'\''"#))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requests() -> Vec<http::Request<bytes::Bytes>> {
+        vec![
+            http::Request::builder()
+                .method(http::Method::GET)
+                .uri("https://api.indexnow.org/indexnow?url=https%3A%2F%2Fwww.example.com%2F&key=687a308e4eff49f994d89eb22f764514")
+                .body(bytes::Bytes::new())
+                .unwrap(),
+            http::Request::builder()
+                .method(http::Method::POST)
+                .uri("https://api.indexnow.org/indexnow")
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(bytes::Bytes::from_static(br#"{"urlList":["https://www.example.com/it's"]}"#))
+                .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_render_curl() {
+        assert_eq!(
+            render_requests(&requests(), RequestFormat::Curl),
+            r#"curl -X GET 'https://api.indexnow.org/indexnow?url=https%3A%2F%2Fwww.example.com%2F&key=687a308e4eff49f994d89eb22f764514'
+curl -X POST 'https://api.indexnow.org/indexnow' -H 'content-type: application/json' --data-binary '{"urlList":["https://www.example.com/it'\''s"]}'
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_http() {
+        assert_eq!(
+            render_requests(&requests()[1..], RequestFormat::Http),
+            "POST /indexnow HTTP/1.1\r\nhost: api.indexnow.org\r\ncontent-type: application/json\r\ncontent-length: 44\r\n\r\n{\"urlList\":[\"https://www.example.com/it's\"]}"
+        );
+    }
+
+    #[test]
+    fn test_render_json() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_requests(&requests(), RequestFormat::Json))?;
+
+        assert_json_diff::assert_json_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "method": "GET",
+                    "uri": "https://api.indexnow.org/indexnow?url=https%3A%2F%2Fwww.example.com%2F&key=687a308e4eff49f994d89eb22f764514",
+                    "headers": {},
+                    "body": null,
+                },
+                {
+                    "method": "POST",
+                    "uri": "https://api.indexnow.org/indexnow",
+                    "headers": {"content-type": "application/json"},
+                    "body": {"urlList": ["https://www.example.com/it's"]},
+                },
+            ])
+        );
+
+        Ok(())
+    }
+}
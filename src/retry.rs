@@ -0,0 +1,171 @@
+/// When and how long to wait before retrying a submission
+///
+/// Submissions answered with `429 Too Many Requests` or a `5xx` server error are retried with
+/// exponential backoff, unless the response has a `Retry-After` header to wait for instead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts including the first one
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, doubled for each further retry
+    pub base_delay: std::time::Duration,
+
+    /// Upper bound of any delay, retrying is given up for a longer `Retry-After`
+    pub max_delay: std::time::Duration,
+
+    /// Whether to randomize delays to spread out retries
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay: std::time::Duration::from_secs(1),
+            max_delay: std::time::Duration::from_secs(60),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy with the given number of retries after the first attempt
+    pub fn with_retries(retries: u32) -> Self {
+        Self {
+            max_attempts: retries.saturating_add(1),
+            ..Self::default()
+        }
+    }
+
+    /// Delay before the next attempt, or `None` if the response is not to be retried
+    pub fn retry_delay(
+        &self,
+        attempt: u32,
+        response: &http::Response<bytes::Bytes>,
+    ) -> Option<std::time::Duration> {
+        if attempt >= self.max_attempts || !is_retryable(response.status()) {
+            return None;
+        }
+
+        match retry_after(response.headers(), std::time::SystemTime::now()) {
+            Some(delay) if delay > self.max_delay => None,
+            Some(delay) => Some(delay),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> std::time::Duration {
+        let delay = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+
+        if self.jitter {
+            use rand::Rng as _;
+
+            rand::thread_rng().gen_range(std::time::Duration::ZERO..=delay)
+        } else {
+            delay
+        }
+    }
+}
+
+fn is_retryable(status: http::StatusCode) -> bool {
+    status == http::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Parses the `Retry-After` header, either as delay in seconds or as HTTP date
+fn retry_after(
+    headers: &http::HeaderMap,
+    now: std::time::SystemTime,
+) -> Option<std::time::Duration> {
+    let value = headers.get(http::header::RETRY_AFTER)?;
+
+    if let Some(seconds) = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+    {
+        return Some(std::time::Duration::from_secs(seconds));
+    }
+
+    let date = <headers::Date as headers::Header>::decode(&mut std::iter::once(value)).ok()?;
+    Some(
+        std::time::SystemTime::from(date)
+            .duration_since(now)
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 4,
+            base_delay: std::time::Duration::from_secs(1),
+            max_delay: std::time::Duration::from_secs(3),
+            jitter: false,
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        let response = crate::testing::response(503, "");
+
+        assert_eq!(
+            policy().retry_delay(1, &response),
+            Some(std::time::Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy().retry_delay(2, &response),
+            Some(std::time::Duration::from_secs(2))
+        );
+        assert_eq!(
+            policy().retry_delay(3, &response),
+            Some(std::time::Duration::from_secs(3))
+        );
+        assert_eq!(policy().retry_delay(4, &response), None);
+
+        assert_eq!(
+            policy().retry_delay(1, &crate::testing::response(403, "")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_retry_after() {
+        let response = http::Response::builder()
+            .status(429)
+            .header(http::header::RETRY_AFTER, "2")
+            .body(bytes::Bytes::new())
+            .unwrap();
+        assert_eq!(
+            policy().retry_delay(3, &response),
+            Some(std::time::Duration::from_secs(2))
+        );
+
+        let response = http::Response::builder()
+            .status(429)
+            .header(http::header::RETRY_AFTER, "120")
+            .body(bytes::Bytes::new())
+            .unwrap();
+        assert_eq!(policy().retry_delay(1, &response), None);
+    }
+
+    #[test]
+    fn test_retry_after_date() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            http::header::RETRY_AFTER,
+            http::HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+
+        let now = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_445_412_470);
+        assert_eq!(
+            retry_after(&headers, now),
+            Some(std::time::Duration::from_secs(10))
+        );
+    }
+}
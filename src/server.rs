@@ -0,0 +1,307 @@
+//! Serving key files from web applications
+//!
+//! [`KeyFiles`] is a [`tower::Service`] answering requests for key files, e.g. as fallback of an
+//! `axum` router or wrapped by `hyper::service::service_fn`, and a [`tower::Layer`] answering
+//! them in front of an existing service.
+
+/// Key files to serve, each key at `/{key}.txt` and optionally at the path of a key location
+///
+/// Key files are answered to `GET` and `HEAD` requests with the exact key as body and
+/// `text/plain; charset=utf-8` as content type, as required for verification.
+#[derive(Clone)]
+pub struct KeyFiles {
+    /// Keys by the path they are served at
+    paths: std::sync::Arc<std::collections::HashMap<String, crate::Key>>,
+}
+
+/// Body of the responses of [`KeyFiles`]
+pub type KeyFileBody = http_body::Full<bytes::Bytes>;
+
+impl KeyFiles {
+    pub fn new(keys: impl IntoIterator<Item = crate::Key>) -> Self {
+        Self {
+            paths: std::sync::Arc::new(
+                keys.into_iter()
+                    .map(|key| (format!("/{}", key.file_name()), key))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Also serves the key at the path of the key location, e.g. `/catalog/key.txt`
+    pub fn with_key_location(mut self, key_location: &http::Uri, key: crate::Key) -> Self {
+        std::sync::Arc::make_mut(&mut self.paths).insert(key_location.path().to_string(), key);
+        self
+    }
+
+    /// Response to the request if it asks for a key file
+    pub fn response<B>(&self, request: &http::Request<B>) -> Option<http::Response<KeyFileBody>> {
+        if request.method() != http::Method::GET && request.method() != http::Method::HEAD {
+            return None;
+        }
+
+        let key = self.paths.get(request.uri().path())?;
+        let body = if request.method() == http::Method::HEAD {
+            bytes::Bytes::new()
+        } else {
+            bytes::Bytes::copy_from_slice(key.expose_secret().as_bytes())
+        };
+
+        let response = http::Response::builder()
+            .header(http::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .header(http::header::CONTENT_LENGTH, key.expose_secret().len())
+            .body(http_body::Full::new(body))
+            .expect("key file response to be valid");
+
+        Some(response)
+    }
+}
+
+/// Prints the number of key files only, as their paths contain the keys
+impl std::fmt::Debug for KeyFiles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyFiles")
+            .field("len", &self.paths.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B> tower::Service<http::Request<B>> for KeyFiles {
+    type Response = http::Response<KeyFileBody>;
+    type Error = std::convert::Infallible;
+    type Future = std::future::Ready<std::result::Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::result::Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    /// Answers with the key file, or `404 Not Found` for any other request
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let response = self.response(&request).unwrap_or_else(|| {
+            let mut response = http::Response::new(KeyFileBody::default());
+            *response.status_mut() = http::StatusCode::NOT_FOUND;
+            response
+        });
+
+        std::future::ready(Ok(response))
+    }
+}
+
+impl<S> tower::Layer<S> for KeyFiles {
+    type Service = KeyFileMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        KeyFileMiddleware {
+            key_files: self.clone(),
+            inner,
+        }
+    }
+}
+
+/// Service answering requests for key files and passing all others to the inner service, see
+/// [`KeyFiles`]
+#[derive(Clone, Debug)]
+pub struct KeyFileMiddleware<S> {
+    key_files: KeyFiles,
+    inner: S,
+}
+
+impl<S, B, ResBody> tower::Service<http::Request<B>> for KeyFileMiddleware<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Response = http::Response<EitherBody<ResBody>>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<
+            dyn std::future::Future<Output = std::result::Result<Self::Response, Self::Error>>
+                + Send,
+        >,
+    >;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        match self.key_files.response(&request) {
+            Some(response) => {
+                let response = response.map(EitherBody::KeyFile);
+                Box::pin(async move { Ok(response) })
+            }
+            None => {
+                let response = self.inner.call(request);
+                Box::pin(async move { Ok(response.await?.map(EitherBody::Inner)) })
+            }
+        }
+    }
+}
+
+/// Body of the responses of [`KeyFileMiddleware`], either a key file or the inner body
+#[derive(Debug)]
+pub enum EitherBody<B> {
+    KeyFile(KeyFileBody),
+    Inner(B),
+}
+
+impl<B> http_body::Body for EitherBody<B>
+where
+    B: http_body::Body<Data = bytes::Bytes> + Unpin,
+{
+    type Data = bytes::Bytes;
+    type Error = B::Error;
+
+    fn poll_data(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<std::result::Result<Self::Data, Self::Error>>> {
+        match self.get_mut() {
+            Self::KeyFile(body) => http_body::Body::poll_data(std::pin::Pin::new(body), cx)
+                .map(|data| data.map(|data| data.map_err(|never| match never {}))),
+            Self::Inner(body) => http_body::Body::poll_data(std::pin::Pin::new(body), cx),
+        }
+    }
+
+    fn poll_trailers(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::result::Result<Option<http::HeaderMap>, Self::Error>> {
+        match self.get_mut() {
+            Self::KeyFile(body) => http_body::Body::poll_trailers(std::pin::Pin::new(body), cx)
+                .map(|trailers| trailers.map_err(|never| match never {})),
+            Self::Inner(body) => http_body::Body::poll_trailers(std::pin::Pin::new(body), cx),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match self {
+            Self::KeyFile(body) => http_body::Body::is_end_stream(body),
+            Self::Inner(body) => http_body::Body::is_end_stream(body),
+        }
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        match self {
+            Self::KeyFile(body) => http_body::Body::size_hint(body),
+            Self::Inner(body) => http_body::Body::size_hint(body),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_files() -> KeyFiles {
+        KeyFiles::new(["687a308e4eff49f994d89eb22f764514".parse().unwrap()]).with_key_location(
+            &"https://www.example.com/catalog/key.txt".parse().unwrap(),
+            "0123456789abcdef".parse().unwrap(),
+        )
+    }
+
+    fn request(method: http::Method, path: &str) -> http::Request<()> {
+        http::Request::builder()
+            .method(method)
+            .uri(path)
+            .body(())
+            .unwrap()
+    }
+
+    async fn body<B: http_body::Body>(response: http::Response<B>) -> bytes::Bytes
+    where
+        B::Error: std::fmt::Debug,
+    {
+        crate::transport::collect_body(response.into_body(), None)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_key_files() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        use tower::ServiceExt as _;
+
+        for (path, key) in [
+            (
+                "/687a308e4eff49f994d89eb22f764514.txt",
+                "687a308e4eff49f994d89eb22f764514",
+            ),
+            ("/catalog/key.txt", "0123456789abcdef"),
+        ] {
+            let response = key_files()
+                .oneshot(request(http::Method::GET, path))
+                .await?;
+
+            assert_eq!(response.status(), http::StatusCode::OK);
+            assert_eq!(
+                response.headers()[http::header::CONTENT_TYPE],
+                "text/plain; charset=utf-8"
+            );
+            assert_eq!(body(response).await, key);
+        }
+
+        let response = key_files()
+            .oneshot(request(http::Method::HEAD, "/catalog/key.txt"))
+            .await?;
+        assert_eq!(response.headers()[http::header::CONTENT_LENGTH], "16");
+        assert!(body(response).await.is_empty());
+
+        for (method, path) in [
+            (http::Method::GET, "/0123456789abcdef.txt"),
+            (http::Method::GET, "/687a308e4eff49f994d89eb22f764514.txt/"),
+            (http::Method::POST, "/687a308e4eff49f994d89eb22f764514.txt"),
+        ] {
+            let response = key_files().oneshot(request(method, path)).await?;
+            assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_files_redacted() {
+        let key_files = key_files();
+
+        for debug in [
+            format!("{:?}", key_files),
+            format!("{:?}", tower::Layer::layer(&key_files, ())),
+        ] {
+            assert!(!debug.contains("687a308e4eff49f994d89eb22f764514"));
+            assert!(!debug.contains("0123456789abcdef"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_key_file_layer() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        use tower::ServiceExt as _;
+
+        let service = tower::ServiceBuilder::new()
+            .layer(key_files())
+            .service(tower::service_fn(|_request: http::Request<()>| async {
+                Ok::<_, std::convert::Infallible>(http::Response::new(KeyFileBody::from("app")))
+            }));
+
+        let response = service
+            .clone()
+            .oneshot(request(
+                http::Method::GET,
+                "/687a308e4eff49f994d89eb22f764514.txt",
+            ))
+            .await?;
+        assert_eq!(body(response).await, "687a308e4eff49f994d89eb22f764514");
+
+        let response = service
+            .oneshot(request(http::Method::GET, "/index.html"))
+            .await?;
+        assert_eq!(body(response).await, "app");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+//! Integration with `tower` middleware stacks
+//!
+//! [`ServiceTransport`] plugs any HTTP [`tower::Service`] into the clients as transport, and
+//! [`SubmitService`] exposes a single submission as a service to layer middleware around.
+
+/// [`crate::Transport`] sending requests with a [`tower::Service`], e.g. a `hyper` client wrapped
+/// in timeout, concurrency limit or tracing layers
+///
+/// The service is cloned for each request, as with [`tower::ServiceExt::oneshot`]. Requests are
+/// converted to the body type `B` of the service and response bodies are collected.
+pub struct ServiceTransport<S, B = crate::transport::Body> {
+    service: std::sync::Mutex<S>,
+    _body: std::marker::PhantomData<fn() -> B>,
+}
+
+impl<S, B> ServiceTransport<S, B> {
+    pub fn new(service: S) -> Self {
+        Self {
+            service: std::sync::Mutex::new(service),
+            _body: std::marker::PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.service
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl<S, B> std::fmt::Debug for ServiceTransport<S, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceTransport").finish_non_exhaustive()
+    }
+}
+
+impl<S, B, ResBody> crate::Transport for ServiceTransport<S, B>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<ResBody>> + Clone + Send,
+    S::Future: Send,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    B: From<bytes::Bytes> + Send,
+    ResBody: http_body::Body + Send,
+    ResBody::Data: Send,
+    ResBody::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    fn send(
+        &self,
+        request: http::Request<crate::transport::Body>,
+    ) -> crate::transport::ResponseFuture<'_> {
+        let service = self
+            .service
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+
+        Box::pin(async move {
+            use tower::ServiceExt as _;
+
+            let url = crate::transport::without_query(request.uri());
+            let transport_error = |reason: Box<dyn std::error::Error + Send + Sync>| {
+                crate::IndexnowError::Transport {
+                    url: url.clone(),
+                    reason,
+                }
+            };
+
+            let max_body_size = request
+                .extensions()
+                .get::<crate::transport::MaxBodySize>()
+                .copied();
+
+            let (parts, body) = request.into_parts();
+            let body = crate::transport::collect_body(body, None)
+                .await
+                .map_err(|reason| transport_error(Box::new(reason)))?;
+
+            let response = service
+                .oneshot(http::Request::from_parts(parts, B::from(body)))
+                .await
+                .map_err(|reason| transport_error(reason.into()))?;
+
+            let (parts, body) = response.into_parts();
+            let body = if crate::transport::exceeds_max_body_size(&parts.headers, max_body_size) {
+                bytes::Bytes::new()
+            } else {
+                crate::transport::collect_body(body, max_body_size)
+                    .await
+                    .map_err(|reason| transport_error(reason.into()))?
+            };
+
+            Ok(http::Response::from_parts(parts, body))
+        })
+    }
+}
+
+/// Submission of one batch of URLs to one endpoint, see [`SubmitService`]
+#[derive(Clone, Debug)]
+pub struct SubmissionRequest {
+    pub endpoint: http::Uri,
+    pub url_set: crate::UrlSet,
+}
+
+impl SubmissionRequest {
+    pub fn new(endpoint: http::Uri, url_set: crate::UrlSet) -> Self {
+        Self { endpoint, url_set }
+    }
+}
+
+/// [`tower::Service`] submitting a [`SubmissionRequest`] and yielding its
+/// [`crate::SubmissionOutcome`]
+///
+/// Each call sends exactly one request, retries and timeouts are left to the layers around it.
+#[derive(Debug)]
+pub struct SubmitService<T: ?Sized> {
+    transport: std::sync::Arc<T>,
+}
+
+impl<T> SubmitService<T> {
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport: std::sync::Arc::new(transport),
+        }
+    }
+}
+
+impl<T: ?Sized> SubmitService<T> {
+    /// Service sharing the transport, e.g. with an [`crate::IndexnowClient`]
+    pub fn from_arc(transport: std::sync::Arc<T>) -> Self {
+        Self { transport }
+    }
+}
+
+impl<T: ?Sized> Clone for SubmitService<T> {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+        }
+    }
+}
+
+impl<T: crate::Transport + ?Sized + 'static> tower::Service<SubmissionRequest>
+    for SubmitService<T>
+{
+    type Response = crate::SubmissionOutcome;
+    type Error = crate::IndexnowError;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = crate::Result<crate::SubmissionOutcome>> + Send>,
+    >;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<crate::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: SubmissionRequest) -> Self::Future {
+        let transport = self.transport.clone();
+
+        Box::pin(async move {
+            let http_request = request.url_set.request(request.endpoint)?;
+            let response = transport
+                .send(http_request.map(crate::transport::Body::new))
+                .await?;
+
+            Ok(crate::SubmissionOutcome::from_response(response))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_service_transport() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+
+        let service = {
+            let requests = requests.clone();
+            tower::service_fn(
+                move |request: http::Request<http_body::Full<bytes::Bytes>>| {
+                    requests.lock().unwrap().push(request.uri().clone());
+
+                    async move {
+                        Ok::<_, std::convert::Infallible>(http::Response::new(
+                            http_body::Full::new(bytes::Bytes::from_static(b"accepted")),
+                        ))
+                    }
+                },
+            )
+        };
+
+        let client = crate::IndexnowClient::builder()
+            .transport(ServiceTransport::new(service))
+            .key("687a308e4eff49f994d89eb22f764514".parse()?)
+            .build()?;
+
+        let report = client
+            .submit_url("https://www.example.com/product.html".parse()?)
+            .await?;
+
+        assert!(report.is_accepted());
+        match &report.batches[0].result {
+            Ok(outcome) => assert_eq!(outcome.body(), "accepted"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(
+            *requests.lock().unwrap(),
+            ["https://api.indexnow.org/indexnow?url=https%3A%2F%2Fwww.example.com%2Fproduct.html&key=687a308e4eff49f994d89eb22f764514"]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_service() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        use tower::ServiceExt as _;
+
+        let transport =
+            std::sync::Arc::new(crate::testing::ScriptedTransport::with_statuses([422]));
+        let service = tower::ServiceBuilder::new()
+            .map_response(|outcome: crate::SubmissionOutcome| outcome.name())
+            .service(SubmitService::from_arc(transport.clone()));
+
+        let url_set = crate::UrlSet::new(
+            "687a308e4eff49f994d89eb22f764514".parse()?,
+            None,
+            vec![
+                "https://www.example.com/url1".parse()?,
+                "https://www.example.com/url2".parse()?,
+            ],
+        )?;
+
+        let outcome = service
+            .oneshot(SubmissionRequest::new(
+                crate::DEFAULT_ENDPOINT.clone(),
+                url_set,
+            ))
+            .await?;
+
+        assert_eq!(outcome, "unprocessable_urls");
+
+        let requests = transport.take_requests();
+        assert_eq!(requests[0].method(), http::Method::POST);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+/// URL entry of a sitemap
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SitemapEntry {
+    pub loc: http::Uri,
+    pub lastmod: Option<String>,
+}
+
+/// Limits for reading sitemaps and nested sitemap indexes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SitemapLimits {
+    /// Maximum nesting of sitemap indexes
+    pub max_depth: usize,
+
+    /// Maximum size in bytes of each (decompressed) sitemap
+    pub max_size: usize,
+}
+
+impl Default for SitemapLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_size: 50 * 1024 * 1024,
+        }
+    }
+}
+
+/// Reason why a sitemap could not be parsed
+#[derive(Debug, thiserror::Error)]
+pub enum SitemapError {
+    #[error("Invalid UTF-8")]
+    InvalidUtf8,
+
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+
+    #[error("Unexpected root element <{0}>")]
+    UnexpectedRoot(String),
+
+    #[error("Invalid URL {0:?}")]
+    InvalidUrl(String),
+
+    #[error("Sitemap indexes nested deeper than {0} levels")]
+    TooDeep(usize),
+}
+
+enum Sitemap {
+    UrlSet(Vec<SitemapEntry>),
+    Index(Vec<http::Uri>),
+}
+
+/// Reads the URL entries of a sitemap, following sitemap indexes
+///
+/// Both sitemaps and sitemap indexes can be gzip-compressed. Sitemaps referenced by an index are
+/// fetched using the transport.
+pub async fn read_sitemap<T: crate::Transport + ?Sized>(
+    transport: &T,
+    source: &crate::Source,
+    limits: &SitemapLimits,
+) -> crate::Result<Vec<SitemapEntry>> {
+    let mut entries = vec![];
+    let mut pending = std::collections::VecDeque::from([(source.clone(), 0)]);
+
+    while let Some((source, depth)) = pending.pop_front() {
+        let content = crate::source::read(transport, &source, limits.max_size).await?;
+
+        let invalid_sitemap = |reason| crate::IndexnowError::InvalidSitemap {
+            location: source.to_string(),
+            reason,
+        };
+
+        match parse(&content).map_err(invalid_sitemap)? {
+            Sitemap::UrlSet(urls) => entries.extend(urls),
+            Sitemap::Index(sitemaps) => {
+                if depth >= limits.max_depth {
+                    return Err(invalid_sitemap(SitemapError::TooDeep(limits.max_depth)));
+                }
+
+                pending.extend(
+                    sitemaps
+                        .into_iter()
+                        .map(|sitemap| (crate::Source::Url(sitemap), depth + 1)),
+                );
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+fn parse(content: &[u8]) -> std::result::Result<Sitemap, SitemapError> {
+    let content = std::str::from_utf8(content).map_err(|_| SitemapError::InvalidUtf8)?;
+    let document = roxmltree::Document::parse(content)?;
+    let root = document.root_element();
+
+    match root.tag_name().name() {
+        "urlset" => Ok(Sitemap::UrlSet(
+            crate::xml::children(root, "url")
+                .map(|url| {
+                    Ok(SitemapEntry {
+                        loc: loc(url)?,
+                        lastmod: crate::xml::child_text(url, "lastmod").map(str::to_string),
+                    })
+                })
+                .collect::<std::result::Result<_, SitemapError>>()?,
+        )),
+        "sitemapindex" => Ok(Sitemap::Index(
+            crate::xml::children(root, "sitemap")
+                .map(loc)
+                .collect::<std::result::Result<_, SitemapError>>()?,
+        )),
+        name => Err(SitemapError::UnexpectedRoot(name.to_string())),
+    }
+}
+
+fn loc(node: roxmltree::Node<'_, '_>) -> std::result::Result<http::Uri, SitemapError> {
+    let loc = crate::xml::child_text(node, "loc").unwrap_or_default();
+
+    crate::xml::absolute_url(loc).ok_or_else(|| SitemapError::InvalidUrl(loc.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SITEMAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>https://www.example.com/url1</loc>
+    <lastmod>2022-08-01</lastmod>
+  </url>
+  <url>
+    <loc> https://www.example.com/folder/url2?a=1&amp;b=2 </loc>
+  </url>
+</urlset>"#;
+
+    const SITEMAP_INDEX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap>
+    <loc>https://www.example.com/sitemap1.xml</loc>
+  </sitemap>
+  <sitemap>
+    <loc>https://www.example.com/sitemap2.xml</loc>
+  </sitemap>
+</sitemapindex>"#;
+
+    #[tokio::test]
+    async fn test_read_sitemap_index() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::new([
+            crate::testing::response(200, SITEMAP_INDEX),
+            crate::testing::response(200, SITEMAP),
+            crate::testing::response(
+                200,
+                r#"<urlset><url><loc>https://www.example.com/url3</loc></url></urlset>"#,
+            ),
+        ]);
+
+        let entries = read_sitemap(
+            &transport,
+            &"https://www.example.com/sitemap_index.xml".parse()?,
+            &SitemapLimits::default(),
+        )
+        .await?;
+
+        assert_eq!(
+            entries,
+            [
+                SitemapEntry {
+                    loc: "https://www.example.com/url1".parse()?,
+                    lastmod: Some("2022-08-01".to_string()),
+                },
+                SitemapEntry {
+                    loc: "https://www.example.com/folder/url2?a=1&b=2".parse()?,
+                    lastmod: None,
+                },
+                SitemapEntry {
+                    loc: "https://www.example.com/url3".parse()?,
+                    lastmod: None,
+                },
+            ]
+        );
+
+        let requests = transport.take_requests();
+        assert_eq!(*requests[2].uri(), "https://www.example.com/sitemap2.xml");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_sitemap_too_deep() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport =
+            crate::testing::ScriptedTransport::new([crate::testing::response(200, SITEMAP_INDEX)]);
+
+        let result = read_sitemap(
+            &transport,
+            &"https://www.example.com/sitemap_index.xml".parse()?,
+            &SitemapLimits {
+                max_depth: 0,
+                ..SitemapLimits::default()
+            },
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::IndexnowError::InvalidSitemap {
+                reason: SitemapError::TooDeep(0),
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(matches!(
+            parse(b"<rss/>"),
+            Err(SitemapError::UnexpectedRoot(_))
+        ));
+        assert!(matches!(
+            parse(b"<urlset><url><loc>/relative</loc></url></urlset>"),
+            Err(SitemapError::InvalidUrl(_))
+        ));
+    }
+}
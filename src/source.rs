@@ -0,0 +1,194 @@
+/// Location of a document to read URLs from, either a local file or an HTTP(S) URL
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    File(std::path::PathBuf),
+    Url(http::Uri),
+}
+
+impl std::str::FromStr for Source {
+    type Err = http::uri::InvalidUri;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let lowercase = s.to_ascii_lowercase();
+        if lowercase.starts_with("http://") || lowercase.starts_with("https://") {
+            Ok(Self::Url(s.parse()?))
+        } else {
+            Ok(Self::File(s.into()))
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Url(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// Reason why a source could not be read
+#[derive(Debug, thiserror::Error)]
+pub enum ReadError {
+    #[error("Unexpected status {0}")]
+    Status(http::StatusCode),
+
+    #[error("Larger than {0} bytes")]
+    TooLarge(usize),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Reads the whole source of at most `max_size` bytes, decompressing it if gzip-compressed
+pub(crate) async fn read<T: crate::Transport + ?Sized>(
+    transport: &T,
+    source: &Source,
+    max_size: usize,
+) -> crate::Result<Vec<u8>> {
+    let content = match source {
+        Source::File(path) => read_file(path, max_size),
+        Source::Url(url) => fetch(transport, url, max_size).await?,
+    };
+
+    content
+        .and_then(|content| decompress(content, max_size))
+        .map_err(|reason| crate::IndexnowError::Read {
+            location: source.to_string(),
+            reason,
+        })
+}
+
+fn read_file(path: &std::path::Path, max_size: usize) -> std::result::Result<Vec<u8>, ReadError> {
+    use std::io::Read as _;
+
+    let mut content = vec![];
+    std::fs::File::open(path)?
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut content)?;
+
+    if content.len() > max_size {
+        return Err(ReadError::TooLarge(max_size));
+    }
+
+    Ok(content)
+}
+
+async fn fetch<T: crate::Transport + ?Sized>(
+    transport: &T,
+    url: &http::Uri,
+    max_size: usize,
+) -> crate::Result<std::result::Result<Vec<u8>, ReadError>> {
+    let request = http::Request::builder()
+        .method(http::Method::GET)
+        .uri(url.clone())
+        .extension(crate::transport::MaxBodySize(max_size))
+        .body(crate::transport::Body::new(bytes::Bytes::new()))
+        .map_err(|reason| crate::invalid_uri(&url.to_string(), reason))?;
+
+    let response = transport.send(request).await?;
+
+    // The transport may stop reading large bodies early, so check the announced length as well
+    let too_large = response.body().len() > max_size
+        || crate::transport::exceeds_max_body_size(
+            response.headers(),
+            Some(crate::transport::MaxBodySize(max_size)),
+        );
+
+    Ok(if !response.status().is_success() {
+        Err(ReadError::Status(response.status()))
+    } else if too_large {
+        Err(ReadError::TooLarge(max_size))
+    } else {
+        Ok(response.into_body().to_vec())
+    })
+}
+
+fn decompress(content: Vec<u8>, max_size: usize) -> std::result::Result<Vec<u8>, ReadError> {
+    use std::io::Read as _;
+
+    if !content.starts_with(&[0x1f, 0x8b]) {
+        return Ok(content);
+    }
+
+    let mut decompressed = vec![];
+    flate2::read::GzDecoder::new(content.as_slice())
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut decompressed)?;
+
+    if decompressed.len() > max_size {
+        return Err(ReadError::TooLarge(max_size));
+    }
+
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_from_str() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            "https://www.example.com/sitemap.xml".parse::<Source>()?,
+            Source::Url("https://www.example.com/sitemap.xml".parse()?)
+        );
+        assert_eq!(
+            "public/sitemap.xml".parse::<Source>()?,
+            Source::File("public/sitemap.xml".into())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_gzip() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        use std::io::Write as _;
+
+        let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(b"<urlset/>")?;
+        let compressed = encoder.finish()?;
+
+        let transport = crate::testing::ScriptedTransport::new([
+            http::Response::new(bytes::Bytes::from(compressed.clone())),
+            http::Response::new(bytes::Bytes::from(compressed)),
+        ]);
+        let source = "https://www.example.com/sitemap.xml.gz".parse()?;
+
+        assert_eq!(read(&transport, &source, 1024).await?, b"<urlset/>");
+        assert!(matches!(
+            read(&transport, &source, 4).await,
+            Err(crate::IndexnowError::Read {
+                reason: ReadError::TooLarge(4),
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_max_size() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        // Transports may leave the body empty if the announced length is too large
+        let transport = crate::testing::ScriptedTransport::new([http::Response::builder()
+            .header(http::header::CONTENT_LENGTH, 2048)
+            .body(bytes::Bytes::new())?]);
+        let source = "https://www.example.com/sitemap.xml".parse()?;
+
+        assert!(matches!(
+            read(&transport, &source, 1024).await,
+            Err(crate::IndexnowError::Read {
+                reason: ReadError::TooLarge(1024),
+                ..
+            })
+        ));
+        assert_eq!(
+            transport.take_requests()[0]
+                .extensions()
+                .get::<crate::transport::MaxBodySize>(),
+            Some(&crate::transport::MaxBodySize(1024))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+/// Reason why a state file could not be loaded or saved
+#[derive(Debug, thiserror::Error)]
+pub enum StateError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Fingerprints of previously submitted sitemap URLs, persisted between runs
+///
+/// A fingerprint is the `<lastmod>` of a sitemap entry, or a hash of the page content if the entry
+/// has no `<lastmod>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SitemapState {
+    urls: std::collections::BTreeMap<String, String>,
+}
+
+/// Sitemap URLs that are new, changed or removed compared to a [`SitemapState`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SitemapChanges {
+    pub added: Vec<http::Uri>,
+    pub changed: Vec<http::Uri>,
+    pub removed: Vec<http::Uri>,
+    fingerprints: std::collections::HashMap<http::Uri, String>,
+}
+
+impl SitemapChanges {
+    /// All URLs to be submitted
+    pub fn urls(&self) -> Vec<http::Uri> {
+        self.added
+            .iter()
+            .chain(&self.changed)
+            .chain(&self.removed)
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl SitemapState {
+    /// Loads the state file, or an empty state if it does not exist yet
+    pub fn load(path: &std::path::Path) -> crate::Result<Self> {
+        let content = match std::fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(state_error(path, e.into())),
+        };
+
+        serde_json::from_slice(&content).map_err(|e| state_error(path, e.into()))
+    }
+
+    /// Saves the state file, replacing it atomically
+    pub fn save(&self, path: &std::path::Path) -> crate::Result<()> {
+        let content = serde_json::to_vec_pretty(self).map_err(|e| state_error(path, e.into()))?;
+
+        let temporary_path = path.with_extension("tmp");
+        std::fs::write(&temporary_path, content)
+            .and_then(|_| std::fs::rename(&temporary_path, path))
+            .map_err(|e| state_error(path, e.into()))
+    }
+
+    /// Compares sitemap entries against the state
+    ///
+    /// Pages of entries without `<lastmod>` are fetched using the transport to hash their content.
+    pub async fn changes<T: crate::Transport + ?Sized>(
+        &self,
+        transport: &T,
+        entries: &[crate::SitemapEntry],
+        max_size: usize,
+    ) -> crate::Result<SitemapChanges> {
+        let mut changes = SitemapChanges::default();
+        let mut seen = std::collections::HashSet::new();
+
+        for entry in entries {
+            if !seen.insert(entry.loc.to_string()) {
+                continue;
+            }
+
+            let fingerprint = match &entry.lastmod {
+                Some(lastmod) => format!("lastmod:{}", lastmod),
+                None => content_hash(transport, &entry.loc, max_size).await?,
+            };
+
+            match self.urls.get(&entry.loc.to_string()) {
+                None => changes.added.push(entry.loc.clone()),
+                Some(previous) if *previous != fingerprint => {
+                    changes.changed.push(entry.loc.clone())
+                }
+                Some(_) => continue,
+            }
+
+            changes.fingerprints.insert(entry.loc.clone(), fingerprint);
+        }
+
+        for url in self.urls.keys().filter(|url| !seen.contains(*url)) {
+            changes.removed.push(
+                url.parse()
+                    .map_err(|reason: http::uri::InvalidUri| crate::invalid_uri(url, reason))?,
+            );
+        }
+
+        Ok(changes)
+    }
+
+    /// Records the changes of the accepted URLs, other changes are compared again next time
+    pub fn apply(&mut self, changes: &SitemapChanges, accepted_urls: &[http::Uri]) {
+        for url in accepted_urls {
+            match changes.fingerprints.get(url) {
+                Some(fingerprint) => {
+                    self.urls.insert(url.to_string(), fingerprint.clone());
+                }
+                None if changes.removed.contains(url) => {
+                    self.urls.remove(&url.to_string());
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+async fn content_hash<T: crate::Transport + ?Sized>(
+    transport: &T,
+    url: &http::Uri,
+    max_size: usize,
+) -> crate::Result<String> {
+    use sha2::Digest as _;
+
+    let content =
+        crate::source::read(transport, &crate::Source::Url(url.clone()), max_size).await?;
+
+    Ok(format!("sha256:{:x}", sha2::Sha256::digest(&content)))
+}
+
+fn state_error(path: &std::path::Path, reason: StateError) -> crate::IndexnowError {
+    crate::IndexnowError::State {
+        location: path.display().to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(loc: &str, lastmod: Option<&str>) -> crate::SitemapEntry {
+        crate::SitemapEntry {
+            loc: loc.parse().unwrap(),
+            lastmod: lastmod.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changes() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::new([
+            crate::testing::response(200, "content"),
+            crate::testing::response(200, "content"),
+        ]);
+
+        let entries = [
+            entry("https://www.example.com/url1", Some("2022-08-01")),
+            entry("https://www.example.com/url2", None),
+            entry("https://www.example.com/url3", Some("2022-08-01")),
+        ];
+
+        let mut state = SitemapState::default();
+        let changes = state.changes(&transport, &entries, 1024).await?;
+        assert_eq!(changes.added.len(), 3);
+        state.apply(&changes, &changes.urls());
+
+        let changes = state.changes(&transport, &entries, 1024).await?;
+        assert!(changes.is_empty());
+
+        let entries = [
+            entry("https://www.example.com/url1", Some("2022-08-02")),
+            entry("https://www.example.com/url2", None),
+            entry("https://www.example.com/url4", None),
+        ];
+        let transport = crate::testing::ScriptedTransport::new([
+            crate::testing::response(200, "changed content"),
+            crate::testing::response(200, "content"),
+        ]);
+
+        let changes = state.changes(&transport, &entries, 1024).await?;
+        assert_eq!(changes.added, ["https://www.example.com/url4"]);
+        assert_eq!(
+            changes.changed,
+            [
+                "https://www.example.com/url1",
+                "https://www.example.com/url2"
+            ]
+        );
+        assert_eq!(changes.removed, ["https://www.example.com/url3"]);
+
+        state.apply(&changes, &["https://www.example.com/url3".parse()?]);
+        let changes = state.changes(&transport, &entries[..1], 1024).await?;
+        assert_eq!(changes.removed, ["https://www.example.com/url2"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_save() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join(format!("indexnow-state-{}.json", std::process::id()));
+
+        assert_eq!(SitemapState::load(&path)?, SitemapState::default());
+
+        let mut state = SitemapState::default();
+        state.urls.insert(
+            "https://www.example.com/url1".to_string(),
+            "lastmod:2022-08-01".to_string(),
+        );
+        state.save(&path)?;
+
+        assert_eq!(SitemapState::load(&path)?, state);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
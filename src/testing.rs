@@ -0,0 +1,203 @@
+/// In-memory [`crate::Transport`] and [`crate::blocking::Transport`] answering with scripted responses and recording the requests
+pub(crate) struct ScriptedTransport {
+    responses: std::sync::Mutex<std::collections::VecDeque<http::Response<bytes::Bytes>>>,
+    requests: std::sync::Mutex<Vec<http::Request<crate::transport::Body>>>,
+}
+
+impl ScriptedTransport {
+    pub(crate) fn new(responses: impl IntoIterator<Item = http::Response<bytes::Bytes>>) -> Self {
+        Self {
+            responses: std::sync::Mutex::new(responses.into_iter().collect()),
+            requests: std::sync::Mutex::new(vec![]),
+        }
+    }
+
+    pub(crate) fn with_statuses(statuses: impl IntoIterator<Item = u16>) -> Self {
+        Self::new(statuses.into_iter().map(|status| response(status, "")))
+    }
+
+    /// Takes the requests sent so far
+    pub(crate) fn take_requests(&self) -> Vec<http::Request<crate::transport::Body>> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+impl crate::Transport for ScriptedTransport {
+    fn send(
+        &self,
+        request: http::Request<crate::transport::Body>,
+    ) -> crate::transport::ResponseFuture<'_> {
+        self.requests.lock().unwrap().push(request);
+        let response = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .expect("scripted response for request");
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::blocking::Transport for ScriptedTransport {
+    fn send(
+        &self,
+        request: http::Request<bytes::Bytes>,
+    ) -> crate::Result<http::Response<bytes::Bytes>> {
+        self.requests
+            .lock()
+            .unwrap()
+            .push(request.map(crate::transport::Body::new));
+        Ok(self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .expect("scripted response for request"))
+    }
+}
+
+/// [`crate::Transport`] and [`crate::blocking::Transport`] failing to send any request
+pub(crate) struct FailingTransport;
+
+impl crate::Transport for FailingTransport {
+    fn send(
+        &self,
+        request: http::Request<crate::transport::Body>,
+    ) -> crate::transport::ResponseFuture<'_> {
+        let url = request.uri().clone();
+        Box::pin(async move {
+            Err(crate::IndexnowError::Transport {
+                url,
+                reason: "connection refused".into(),
+            })
+        })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::blocking::Transport for FailingTransport {
+    fn send(
+        &self,
+        request: http::Request<bytes::Bytes>,
+    ) -> crate::Result<http::Response<bytes::Bytes>> {
+        Err(crate::IndexnowError::Transport {
+            url: request.uri().clone(),
+            reason: "connection refused".into(),
+        })
+    }
+}
+
+/// [`crate::Transport`] that never answers, to exercise timeouts
+pub(crate) struct PendingTransport;
+
+impl crate::Transport for PendingTransport {
+    fn send(
+        &self,
+        _request: http::Request<crate::transport::Body>,
+    ) -> crate::transport::ResponseFuture<'_> {
+        Box::pin(futures_util::future::pending())
+    }
+}
+
+pub(crate) fn response(status: u16, body: &str) -> http::Response<bytes::Bytes> {
+    http::Response::builder()
+        .status(status)
+        .body(bytes::Bytes::copy_from_slice(body.as_bytes()))
+        .unwrap()
+}
+
+/// HTTP server on a loopback port answering one raw response per connection and recording the raw
+/// requests, to drive [`crate::HyperTransport`] over real connections
+#[cfg(feature = "hyper-rustls")]
+pub(crate) struct LoopbackServer {
+    address: std::net::SocketAddr,
+    requests: tokio::task::JoinHandle<std::io::Result<Vec<String>>>,
+}
+
+#[cfg(feature = "hyper-rustls")]
+impl LoopbackServer {
+    pub(crate) async fn start(responses: Vec<String>) -> std::io::Result<Self> {
+        use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+
+        let requests = tokio::spawn(async move {
+            let mut requests = vec![];
+
+            for response in responses {
+                let (mut stream, _) = listener.accept().await?;
+
+                let mut request = vec![];
+                let mut buffer = [0; 1024];
+                while !is_complete_request(&request) {
+                    let read = stream.read(&mut buffer).await?;
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                requests.push(String::from_utf8_lossy(&request).into_owned());
+
+                stream.write_all(response.as_bytes()).await?;
+                stream.shutdown().await?;
+            }
+
+            Ok(requests)
+        });
+
+        Ok(Self { address, requests })
+    }
+
+    /// URL of the path on the server
+    pub(crate) fn url(&self, path: &str) -> http::Uri {
+        format!("http://{}{}", self.address, path)
+            .parse()
+            .expect("loopback URL to be valid")
+    }
+
+    /// Waits until all responses were sent and returns the requests received
+    pub(crate) async fn requests(self) -> std::io::Result<Vec<String>> {
+        self.requests.await.expect("loopback server not to panic")
+    }
+}
+
+/// Whether the raw request has its head and as much body as its `Content-Length` announces
+#[cfg(feature = "hyper-rustls")]
+fn is_complete_request(request: &[u8]) -> bool {
+    let head_end = match request.windows(4).position(|window| window == b"\r\n\r\n") {
+        Some(head_end) => head_end + 4,
+        None => return false,
+    };
+
+    let content_length = String::from_utf8_lossy(&request[..head_end])
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse().ok())
+        .unwrap_or(0);
+
+    request.len() >= head_end + content_length
+}
+
+/// Raw HTTP/1.1 response closing the connection, for the [`LoopbackServer`]
+#[cfg(feature = "hyper-rustls")]
+pub(crate) fn raw_response(status: u16, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!(
+        "HTTP/1.1 {} {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+        status,
+        http::StatusCode::from_u16(status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or(""),
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+
+    response
+}
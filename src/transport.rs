@@ -0,0 +1,217 @@
+/// Body of the requests built for the `IndexNow.org` API
+pub type Body = http_body::Full<bytes::Bytes>;
+
+/// Future returned by [`Transport::send`]
+pub type ResponseFuture<'a> = std::pin::Pin<
+    Box<dyn std::future::Future<Output = crate::Result<http::Response<bytes::Bytes>>> + Send + 'a>,
+>;
+
+/// Request extension asking the transport to stop collecting the response body once it is larger
+/// than this many bytes, e.g. for documents with a size limit
+///
+/// The body is then cut off after one byte more than the limit, or left empty if the
+/// `Content-Length` header already exceeds it. Transports may ignore the extension, so callers
+/// still have to check the size of the body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxBodySize(pub usize);
+
+/// Sends built requests to an `IndexNow.org` endpoint
+///
+/// Implementations receive the fully built request and resolve to the response with its body
+/// already collected, so that alternative HTTP clients or in-memory stand-ins can be plugged in.
+pub trait Transport: Send + Sync {
+    fn send(&self, request: http::Request<Body>) -> ResponseFuture<'_>;
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+    fn send(&self, request: http::Request<Body>) -> ResponseFuture<'_> {
+        (**self).send(request)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn send(&self, request: http::Request<Body>) -> ResponseFuture<'_> {
+        (**self).send(request)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    fn send(&self, request: http::Request<Body>) -> ResponseFuture<'_> {
+        (**self).send(request)
+    }
+}
+
+/// Default [`Transport`] using `hyper` with `rustls`
+#[cfg(feature = "hyper-rustls")]
+#[derive(Clone)]
+pub struct HyperTransport {
+    client: hyper::Client<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>, Body>,
+    timeout: Option<std::time::Duration>,
+}
+
+#[cfg(feature = "hyper-rustls")]
+impl HyperTransport {
+    pub fn new() -> Self {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+
+        Self {
+            client: hyper::Client::builder().build(connector),
+            timeout: None,
+        }
+    }
+
+    /// Transport giving up on requests that take longer than `timeout`, including the response
+    ///
+    /// Unlike [`crate::IndexnowClientBuilder::timeout`], this also applies to fetching sitemaps,
+    /// feeds and key files.
+    pub fn with_timeout(timeout: std::time::Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..Self::new()
+        }
+    }
+}
+
+#[cfg(feature = "hyper-rustls")]
+impl Default for HyperTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "hyper-rustls")]
+impl std::fmt::Debug for HyperTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HyperTransport")
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "hyper-rustls")]
+impl Transport for HyperTransport {
+    fn send(&self, request: http::Request<Body>) -> ResponseFuture<'_> {
+        Box::pin(async move {
+            let url = without_query(request.uri());
+            let transport_error = |reason: hyper::Error| crate::IndexnowError::Transport {
+                url: url.clone(),
+                reason: Box::new(reason),
+            };
+            let max_body_size = request.extensions().get::<MaxBodySize>().copied();
+
+            let response = async {
+                let response = self
+                    .client
+                    .request(request)
+                    .await
+                    .map_err(transport_error)?;
+
+                let (parts, body) = response.into_parts();
+                let body = if exceeds_max_body_size(&parts.headers, max_body_size) {
+                    bytes::Bytes::new()
+                } else {
+                    collect_body(body, max_body_size)
+                        .await
+                        .map_err(transport_error)?
+                };
+
+                Ok::<_, crate::IndexnowError>(http::Response::from_parts(parts, body))
+            };
+
+            match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, response)
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(crate::IndexnowError::Timeout {
+                            url: url.clone(),
+                            timeout,
+                        })
+                    }),
+                None => response.await,
+            }
+        })
+    }
+}
+
+/// URI without its query, to name the request in errors without exposing the key
+#[cfg(any(feature = "hyper-rustls", feature = "blocking", feature = "tower"))]
+pub(crate) fn without_query(uri: &http::Uri) -> http::Uri {
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = parts
+        .path_and_query
+        .and_then(|path_and_query| path_and_query.path().parse().ok());
+
+    http::Uri::from_parts(parts).unwrap_or_else(|_| uri.clone())
+}
+
+/// Whether the `Content-Length` header already exceeds the [`MaxBodySize`], if any
+pub(crate) fn exceeds_max_body_size(
+    headers: &http::HeaderMap,
+    max_body_size: Option<MaxBodySize>,
+) -> bool {
+    let content_length = headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    match (content_length, max_body_size) {
+        (Some(content_length), Some(MaxBodySize(max_size))) => content_length > max_size as u64,
+        _ => false,
+    }
+}
+
+/// Collects the data of the body, stopping after one byte more than the [`MaxBodySize`], if any
+#[cfg(any(
+    feature = "hyper-rustls",
+    feature = "tower",
+    all(test, feature = "server")
+))]
+pub(crate) async fn collect_body<B: http_body::Body>(
+    body: B,
+    max_body_size: Option<MaxBodySize>,
+) -> std::result::Result<bytes::Bytes, B::Error> {
+    use bytes::BufMut as _;
+    use http_body::Body as _;
+
+    futures_util::pin_mut!(body);
+
+    let mut collected = bytes::BytesMut::new();
+    while let Some(data) = body.data().await {
+        collected.put(data?);
+
+        if let Some(MaxBodySize(max_size)) = max_body_size {
+            if collected.len() > max_size {
+                collected.truncate(max_size + 1);
+                break;
+            }
+        }
+    }
+
+    Ok(collected.freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(any(feature = "hyper-rustls", feature = "tower", feature = "server"))]
+    #[tokio::test]
+    async fn test_collect_body_max_size() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let body = || http_body::Full::new(bytes::Bytes::from_static(b"<urlset></urlset>"));
+
+        assert_eq!(collect_body(body(), None).await?, "<urlset></urlset>");
+        assert_eq!(collect_body(body(), Some(MaxBodySize(4))).await?, "<urls");
+
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::CONTENT_LENGTH, http::HeaderValue::from(17));
+        assert!(exceeds_max_body_size(&headers, Some(MaxBodySize(16))));
+        assert!(!exceeds_max_body_size(&headers, Some(MaxBodySize(17))));
+        assert!(!exceeds_max_body_size(&headers, None));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+/// Reason why a URL list could not be read
+#[derive(Debug, thiserror::Error)]
+pub enum UrlListError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Line {line}: invalid URL {url:?}")]
+    InvalidUrl {
+        line: usize,
+        url: String,
+        #[source]
+        reason: crate::SubmissionUrlError,
+    },
+}
+
+/// Iterator over the URLs of a plain-text list, read line by line
+///
+/// Each line holds one [`crate::SubmissionUrl`]. Blank lines and lines starting with `#` are
+/// skipped.
+#[derive(Debug)]
+pub struct UrlList<R> {
+    lines: std::iter::Enumerate<std::io::Lines<R>>,
+    location: String,
+}
+
+impl<R: std::io::BufRead> UrlList<R> {
+    /// Reads the list from `reader`, with `location` naming it in errors
+    pub fn new(reader: R, location: impl Into<String>) -> Self {
+        Self {
+            lines: reader.lines().enumerate(),
+            location: location.into(),
+        }
+    }
+
+    fn error(&self, reason: UrlListError) -> crate::IndexnowError {
+        crate::IndexnowError::InvalidUrlList {
+            location: self.location.clone(),
+            reason,
+        }
+    }
+}
+
+impl<R: std::io::BufRead> Iterator for UrlList<R> {
+    type Item = crate::Result<crate::SubmissionUrl>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (index, line) = self.lines.next()?;
+
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(self.error(e.into()))),
+            };
+
+            let url = line.trim();
+            if url.is_empty() || url.starts_with('#') {
+                continue;
+            }
+
+            return Some(url.parse().map_err(|reason| {
+                self.error(UrlListError::InvalidUrl {
+                    line: index + 1,
+                    url: url.to_string(),
+                    reason,
+                })
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_list() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let content = "# Changed pages\nhttps://www.example.com/url1\n\n  https://www.example.com/url2  \n/relative\n";
+
+        let mut urls = UrlList::new(content.as_bytes(), "urls.txt");
+
+        assert_eq!(
+            urls.next().transpose()?.unwrap().to_string(),
+            "https://www.example.com/url1"
+        );
+        assert_eq!(
+            urls.next().transpose()?.unwrap().to_string(),
+            "https://www.example.com/url2"
+        );
+        assert!(matches!(
+            urls.next(),
+            Some(Err(crate::IndexnowError::InvalidUrlList {
+                reason: UrlListError::InvalidUrl {
+                    line: 5,
+                    reason: crate::SubmissionUrlError::MissingScheme,
+                    ..
+                },
+                ..
+            }))
+        ));
+        assert!(urls.next().is_none());
+
+        Ok(())
+    }
+}
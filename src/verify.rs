@@ -0,0 +1,342 @@
+/// Reason why a key file does not verify ownership
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum KeyFileError {
+    #[error("Unexpected status {0}")]
+    Status(http::StatusCode),
+
+    #[error("Body starts with a byte order mark")]
+    ByteOrderMark,
+
+    #[error("Body is not valid UTF-8")]
+    InvalidUtf8,
+
+    #[error("Body does not match the key")]
+    KeyMismatch,
+}
+
+/// URL of the key file, either the given key location or `{key}.txt` at the root of the given host
+pub fn key_file_url(
+    key: &crate::Key,
+    key_location_or_host: &http::Uri,
+) -> crate::Result<http::Uri> {
+    let authority = key_location_or_host
+        .authority()
+        .ok_or(crate::IndexnowError::MissingHost)?;
+
+    match key_location_or_host.path() {
+        "" | "/" => http::Uri::builder()
+            .scheme(
+                key_location_or_host
+                    .scheme()
+                    .cloned()
+                    .unwrap_or(http::uri::Scheme::HTTPS),
+            )
+            .authority(authority.clone())
+            .path_and_query(format!("/{}", key.file_name()))
+            .build()
+            .map_err(|reason| crate::invalid_uri(&key_location_or_host.to_string(), reason)),
+        _ => Ok(key_location_or_host.clone()),
+    }
+}
+
+/// Checks that the URLs are authorized by the key location
+///
+/// A key file only authorizes URLs of the same host within its own directory, e.g.
+/// `https://example.com/catalog/key.txt` authorizes `https://example.com/catalog/` and below.
+pub fn check_key_location_scope(key_location: &http::Uri, urls: &[http::Uri]) -> crate::Result<()> {
+    let host = key_location
+        .host()
+        .ok_or(crate::IndexnowError::MissingHost)?;
+    let path = key_location.path();
+    let directory = &path[..path.rfind('/').map_or(0, |i| i + 1)];
+
+    let out_of_scope: Vec<_> = urls
+        .iter()
+        .filter(|url| {
+            !url.host()
+                .map_or(false, |url_host| url_host.eq_ignore_ascii_case(host))
+                || !url.path().starts_with(directory)
+        })
+        .cloned()
+        .collect();
+
+    if out_of_scope.is_empty() {
+        Ok(())
+    } else {
+        Err(crate::IndexnowError::OutOfScope {
+            key_location: key_location.clone(),
+            urls: out_of_scope,
+        })
+    }
+}
+
+/// Fetches the key file and checks that it serves exactly the key
+///
+/// The body has to be UTF-8 without a byte order mark and may only differ from the key by trailing
+/// whitespace.
+pub async fn verify_key_location<T: crate::Transport + ?Sized>(
+    transport: &T,
+    key: &crate::Key,
+    key_location_or_host: &http::Uri,
+) -> crate::Result<()> {
+    let url = key_file_url(key, key_location_or_host)?;
+    // Errors name the key file without the key, which is not published yet if it fails
+    let redacted_url = redact_key_file_url(&url, key);
+
+    let request = http::Request::builder()
+        .method(http::Method::GET)
+        .uri(url)
+        .body(crate::transport::Body::new(bytes::Bytes::new()))
+        .map_err(|reason| crate::invalid_uri(&redacted_url.to_string(), reason))?;
+
+    let response = transport.send(request).await.map_err(|e| match e {
+        crate::IndexnowError::Transport { reason, .. } => crate::IndexnowError::Transport {
+            url: redacted_url.clone(),
+            reason,
+        },
+        crate::IndexnowError::Timeout { timeout, .. } => crate::IndexnowError::Timeout {
+            url: redacted_url.clone(),
+            timeout,
+        },
+        e => e,
+    })?;
+
+    check_key_file(key, &response).map_err(|reason| crate::IndexnowError::InvalidKeyFile {
+        url: redacted_url,
+        reason,
+    })
+}
+
+/// Key file URL with the file name `{key}.txt` replaced by `[redacted].txt`
+fn redact_key_file_url(url: &http::Uri, key: &crate::Key) -> http::Uri {
+    match url.path().strip_suffix(&key.file_name()) {
+        Some(directory) if directory.ends_with('/') => {
+            let mut parts = url.clone().into_parts();
+            parts.path_and_query = format!("{}{}.txt", directory, key).parse().ok();
+
+            http::Uri::from_parts(parts).unwrap_or_default()
+        }
+        _ => url.clone(),
+    }
+}
+
+fn check_key_file(
+    key: &crate::Key,
+    response: &http::Response<bytes::Bytes>,
+) -> std::result::Result<(), KeyFileError> {
+    if !response.status().is_success() {
+        return Err(KeyFileError::Status(response.status()));
+    }
+
+    let body = response.body();
+    if body.starts_with(b"\xEF\xBB\xBF") {
+        return Err(KeyFileError::ByteOrderMark);
+    }
+
+    let body = std::str::from_utf8(body).map_err(|_| KeyFileError::InvalidUtf8)?;
+    if body
+        .trim_end()
+        .parse::<crate::Key>()
+        .map_or(true, |file_key| file_key != *key)
+    {
+        return Err(KeyFileError::KeyMismatch);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> crate::Key {
+        "687a308e4eff49f994d89eb22f764514".parse().unwrap()
+    }
+
+    #[test]
+    fn test_key_file_url() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            key_file_url(&key(), &"www.example.com".parse()?)?,
+            "https://www.example.com/687a308e4eff49f994d89eb22f764514.txt"
+        );
+        assert_eq!(
+            key_file_url(&key(), &"http://www.example.com/".parse()?)?,
+            "http://www.example.com/687a308e4eff49f994d89eb22f764514.txt"
+        );
+        assert_eq!(
+            key_file_url(&key(), &"https://www.example.com/catalog/key.txt".parse()?)?,
+            "https://www.example.com/catalog/key.txt"
+        );
+        assert!(key_file_url(&key(), &"/key.txt".parse()?).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_key_location_scope() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let key_location = "https://www.example.com/catalog/key.txt".parse()?;
+
+        check_key_location_scope(
+            &key_location,
+            &[
+                "https://www.example.com/catalog/".parse()?,
+                "https://WWW.example.com/catalog/shoes/item1.html".parse()?,
+            ],
+        )?;
+
+        match check_key_location_scope(
+            &key_location,
+            &[
+                "https://www.example.com/catalog/item1.html".parse()?,
+                "https://www.example.com/catalogue/item2.html".parse()?,
+                "https://www.example.com/item3.html".parse()?,
+                "https://shop.example.com/catalog/item4.html".parse()?,
+            ],
+        ) {
+            Err(crate::IndexnowError::OutOfScope { urls, .. }) => assert_eq!(
+                urls,
+                [
+                    "https://www.example.com/catalogue/item2.html",
+                    "https://www.example.com/item3.html",
+                    "https://shop.example.com/catalog/item4.html",
+                ]
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        check_key_location_scope(
+            &"https://www.example.com/key.txt".parse()?,
+            &["https://www.example.com/catalog/item1.html".parse()?],
+        )?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_key_location() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::new([crate::testing::response(
+            200,
+            "687a308e4eff49f994d89eb22f764514\r\n",
+        )]);
+
+        verify_key_location(&transport, &key(), &"www.example.com".parse()?).await?;
+
+        let requests = transport.take_requests();
+        assert_eq!(requests[0].method(), http::Method::GET);
+        assert_eq!(
+            *requests[0].uri(),
+            "https://www.example.com/687a308e4eff49f994d89eb22f764514.txt"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_key_location_failures(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::new([
+            crate::testing::response(404, ""),
+            crate::testing::response(200, "\u{FEFF}687a308e4eff49f994d89eb22f764514"),
+            http::Response::new(bytes::Bytes::from_static(b"\xFF\xFE")),
+            crate::testing::response(200, " 687a308e4eff49f994d89eb22f764514"),
+        ]);
+
+        for expected in [
+            KeyFileError::Status(http::StatusCode::NOT_FOUND),
+            KeyFileError::ByteOrderMark,
+            KeyFileError::InvalidUtf8,
+            KeyFileError::KeyMismatch,
+        ] {
+            match verify_key_location(&transport, &key(), &"www.example.com".parse()?).await {
+                Err(crate::IndexnowError::InvalidKeyFile { reason, .. }) => {
+                    assert_eq!(reason, expected)
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "hyper-rustls")]
+    #[tokio::test]
+    async fn test_verify_key_location_loopback(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        use crate::testing::raw_response;
+
+        let server = crate::testing::LoopbackServer::start(vec![
+            raw_response(
+                200,
+                &[("Content-Type", "text/plain; charset=utf-8")],
+                "687a308e4eff49f994d89eb22f764514\n",
+            ),
+            raw_response(404, &[], "Not Found"),
+        ])
+        .await?;
+        let transport = crate::HyperTransport::new();
+
+        verify_key_location(&transport, &key(), &server.url("/")).await?;
+        match verify_key_location(&transport, &key(), &server.url("/catalog/key.txt")).await {
+            Err(crate::IndexnowError::InvalidKeyFile { reason, .. }) => {
+                assert_eq!(reason, KeyFileError::Status(http::StatusCode::NOT_FOUND))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let requests = server.requests().await?;
+        assert!(requests[0].starts_with("GET /687a308e4eff49f994d89eb22f764514.txt HTTP/1.1\r\n"));
+        assert!(requests[1].starts_with("GET /catalog/key.txt HTTP/1.1\r\n"));
+
+        // Nothing listens on the port once the listener is dropped
+        let port = std::net::TcpListener::bind("127.0.0.1:0")?
+            .local_addr()?
+            .port();
+        let error = verify_key_location(
+            &transport,
+            &key(),
+            &format!("http://127.0.0.1:{}/", port).parse()?,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, crate::IndexnowError::Transport { .. }));
+
+        let mut message = error.to_string();
+        let mut source = std::error::Error::source(&error);
+        while let Some(error) = source {
+            message.push_str(&error.to_string());
+            source = error.source();
+        }
+        assert!(!message.contains("687a308e4eff49f994d89eb22f764514"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_key_location_redacted(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let transport = crate::testing::ScriptedTransport::with_statuses([404]);
+
+        let error = verify_key_location(&transport, &key(), &"www.example.com".parse()?)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid key file at https://www.example.com/[redacted].txt"
+        );
+
+        let error = verify_key_location(
+            &crate::testing::FailingTransport,
+            &key(),
+            &"https://www.example.com/catalog/687a308e4eff49f994d89eb22f764514.txt".parse()?,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, crate::IndexnowError::Transport { .. }));
+        assert_eq!(
+            error.to_string(),
+            "Failed to send request to https://www.example.com/catalog/[redacted].txt"
+        );
+
+        Ok(())
+    }
+}
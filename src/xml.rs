@@ -0,0 +1,23 @@
+/// Child elements with the given local name, ignoring namespaces
+pub(crate) fn children<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// Trimmed text of the first child element with the given local name
+pub(crate) fn child_text<'a>(node: roxmltree::Node<'a, '_>, name: &'a str) -> Option<&'a str> {
+    children(node, name)
+        .next()
+        .and_then(|child| child.text())
+        .map(str::trim)
+}
+
+/// Parses an absolute http or https URL, normalized as a [`crate::SubmissionUrl`]
+pub(crate) fn absolute_url(url: &str) -> Option<http::Uri> {
+    url.parse::<crate::SubmissionUrl>()
+        .ok()
+        .map(crate::SubmissionUrl::into_uri)
+}